@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Kernel allocators, and the `gfp_t` flags that steer them.
+//!
+//! The private [`allocator`](super::allocator) module only ever backs the global allocator with a
+//! single, hardcoded `GFP_KERNEL` `kmalloc`. This module is the public counterpart: it exposes
+//! [`Flags`] so callers can pick the right `gfp_t` for their context (e.g. [`flags::GFP_ATOMIC`]
+//! when allocating with a spinlock held), and the [`Allocator`] trait together with [`Kmalloc`],
+//! [`Vmalloc`] and [`KVmalloc`] so callers can pick physically- vs. virtually-contiguous memory.
+//! [`KBox`] is the first (and so far only) collection type built on top of [`Allocator`]; making
+//! the crate's other collection types (e.g. `Vec`) generic over it the same way is left as future
+//! work.
+
+use crate::bindings;
+use crate::error::Error;
+use crate::Result;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+/// A set of `gfp_t` allocation flags, composable with `|`.
+///
+/// See [`flags`] for the named constants re-exported from the kernel's `GFP_*`/`__GFP_*` macros.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Flags(bindings::gfp_t);
+
+impl Flags {
+    /// Returns the raw `gfp_t` value.
+    pub fn as_raw(self) -> bindings::gfp_t {
+        self.0
+    }
+}
+
+impl core::ops::BitOr for Flags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Named `gfp_t` flag constants, re-exported as [`Flags`] values.
+pub mod flags {
+    use super::Flags;
+    use crate::bindings;
+
+    /// The default allocation flags for process context: may sleep, may perform I/O and
+    /// filesystem calls to reclaim memory.
+    pub const GFP_KERNEL: Flags = Flags(bindings::GFP_KERNEL);
+
+    /// Allocation flags for contexts that cannot sleep, e.g. while holding a spinlock or inside
+    /// an interrupt handler.
+    pub const GFP_ATOMIC: Flags = Flags(bindings::GFP_ATOMIC);
+
+    /// Requests that the returned memory be zeroed.
+    pub const __GFP_ZERO: Flags = Flags(bindings::__GFP_ZERO);
+}
+
+pub use flags::{GFP_ATOMIC, GFP_KERNEL, __GFP_ZERO};
+
+/// A kernel memory allocator.
+///
+/// Implementors back one particular `*alloc`/`*realloc`/`k*free` family (e.g. `kmalloc` vs.
+/// `vmalloc`), so that callers, and eventually the crate's collection types, can pick the
+/// allocator that matches the memory they need: physically contiguous for DMA, or virtually
+/// contiguous for large buffers that don't need to be contiguous in physical memory.
+///
+/// # Safety
+///
+/// `alloc` must return either `None` or a pointer to a live allocation of at least `size` bytes,
+/// obtained from the same underlying allocator that `realloc` and `free` operate on. `free` must
+/// only ever be called with a pointer previously returned by `alloc`/`realloc` on this same
+/// allocator, and only once per such pointer.
+pub unsafe trait Allocator {
+    /// Allocates `size` bytes, returning `None` on failure.
+    fn alloc(size: usize, flags: Flags) -> Option<core::ptr::NonNull<u8>>;
+
+    /// Resizes a previous allocation returned by [`Allocator::alloc`]/[`Allocator::realloc`] on
+    /// this same allocator to `new_size` bytes, returning `None` on failure (in which case `ptr`
+    /// is left untouched).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by a prior `alloc`/`realloc` call on this same allocator, and
+    /// must not be used again until this call returns (as it may be moved to a new location).
+    unsafe fn realloc(
+        ptr: core::ptr::NonNull<u8>,
+        new_size: usize,
+        flags: Flags,
+    ) -> Option<core::ptr::NonNull<u8>>;
+
+    /// Frees a previous allocation returned by [`Allocator::alloc`]/[`Allocator::realloc`] on this
+    /// same allocator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by a prior `alloc`/`realloc` call on this same allocator, and
+    /// must not be used again after this call.
+    unsafe fn free(ptr: core::ptr::NonNull<u8>);
+}
+
+/// An [`Allocator`] backed by `kmalloc`: physically contiguous memory.
+pub struct Kmalloc;
+
+// SAFETY: `kmalloc`/`krealloc`/`kfree` form a matched allocate/resize/free family, and `krealloc`
+// accepts a null `ptr` to allocate fresh memory (not used here, since `alloc` goes through
+// `kmalloc` directly).
+unsafe impl Allocator for Kmalloc {
+    fn alloc(size: usize, flags: Flags) -> Option<core::ptr::NonNull<u8>> {
+        // SAFETY: `size` and `flags.as_raw()` are both valid arguments to `kmalloc`.
+        let ptr = unsafe { bindings::kmalloc(size, flags.as_raw()) };
+        core::ptr::NonNull::new(ptr.cast())
+    }
+
+    unsafe fn realloc(
+        ptr: core::ptr::NonNull<u8>,
+        new_size: usize,
+        flags: Flags,
+    ) -> Option<core::ptr::NonNull<u8>> {
+        // SAFETY: The caller guarantees `ptr` came from this allocator, which is exactly what
+        // `krealloc` requires.
+        let ptr = unsafe { bindings::krealloc(ptr.as_ptr().cast(), new_size, flags.as_raw()) };
+        core::ptr::NonNull::new(ptr.cast())
+    }
+
+    unsafe fn free(ptr: core::ptr::NonNull<u8>) {
+        // SAFETY: The caller guarantees `ptr` came from this allocator and is not used again.
+        unsafe { bindings::kfree(ptr.as_ptr().cast()) };
+    }
+}
+
+/// An [`Allocator`] backed by `vmalloc`: virtually contiguous memory, not physically contiguous.
+pub struct Vmalloc;
+
+// SAFETY: `vmalloc`/`vrealloc`/`vfree` form a matched allocate/resize/free family.
+unsafe impl Allocator for Vmalloc {
+    fn alloc(size: usize, flags: Flags) -> Option<core::ptr::NonNull<u8>> {
+        // SAFETY: `size` and `flags.as_raw()` are both valid arguments to `vmalloc`.
+        let ptr = unsafe { bindings::vmalloc(size, flags.as_raw()) };
+        core::ptr::NonNull::new(ptr.cast())
+    }
+
+    unsafe fn realloc(
+        ptr: core::ptr::NonNull<u8>,
+        new_size: usize,
+        flags: Flags,
+    ) -> Option<core::ptr::NonNull<u8>> {
+        // SAFETY: The caller guarantees `ptr` came from this allocator, which is exactly what
+        // `vrealloc` requires.
+        let ptr = unsafe { bindings::vrealloc(ptr.as_ptr().cast(), new_size, flags.as_raw()) };
+        core::ptr::NonNull::new(ptr.cast())
+    }
+
+    unsafe fn free(ptr: core::ptr::NonNull<u8>) {
+        // SAFETY: The caller guarantees `ptr` came from this allocator and is not used again.
+        unsafe { bindings::vfree(ptr.as_ptr().cast()) };
+    }
+}
+
+/// An [`Allocator`] backed by `kvmalloc`: `kmalloc` when possible, falling back to `vmalloc` for
+/// large requests that cannot be satisfied physically contiguous.
+pub struct KVmalloc;
+
+// SAFETY: `kvmalloc`/`kvrealloc`/`kvfree` form a matched allocate/resize/free family.
+unsafe impl Allocator for KVmalloc {
+    fn alloc(size: usize, flags: Flags) -> Option<core::ptr::NonNull<u8>> {
+        // SAFETY: `size` and `flags.as_raw()` are both valid arguments to `kvmalloc`.
+        let ptr = unsafe { bindings::kvmalloc(size, flags.as_raw()) };
+        core::ptr::NonNull::new(ptr.cast())
+    }
+
+    unsafe fn realloc(
+        ptr: core::ptr::NonNull<u8>,
+        new_size: usize,
+        flags: Flags,
+    ) -> Option<core::ptr::NonNull<u8>> {
+        // SAFETY: The caller guarantees `ptr` came from this allocator, which is exactly what
+        // `kvrealloc` requires.
+        let ptr = unsafe { bindings::kvrealloc(ptr.as_ptr().cast(), new_size, flags.as_raw()) };
+        core::ptr::NonNull::new(ptr.cast())
+    }
+
+    unsafe fn free(ptr: core::ptr::NonNull<u8>) {
+        // SAFETY: The caller guarantees `ptr` came from this allocator and is not used again.
+        unsafe { bindings::kvfree(ptr.as_ptr().cast()) };
+    }
+}
+
+/// An owned, heap-allocated `T`, generic over the [`Allocator`] (defaulting to [`Kmalloc`]) used
+/// to allocate it, so that, unlike [`alloc::boxed::Box`] (which always goes through the crate's
+/// single, hardcoded `GFP_KERNEL` global allocator), callers can choose both the `gfp_t` flags and
+/// whether the backing memory is physically or virtually contiguous.
+pub struct KBox<T, A: Allocator = Kmalloc> {
+    ptr: NonNull<T>,
+    _p: PhantomData<(T, A)>,
+}
+
+impl<T, A: Allocator> KBox<T, A> {
+    /// Allocates space for, and moves `x` into, a new [`KBox`].
+    pub fn try_new(x: T, flags: Flags) -> Result<Self> {
+        let ptr = A::alloc(core::mem::size_of::<T>(), flags).ok_or(Error::ENOMEM)?;
+        let ptr = ptr.cast::<T>();
+        // SAFETY: `ptr` was just allocated above with room for at least one `T`, and is not read
+        // until it has been fully written to here.
+        unsafe { ptr.as_ptr().write(x) };
+        Ok(Self {
+            ptr,
+            _p: PhantomData,
+        })
+    }
+
+    /// Consumes the `KBox`, returning the wrapped raw pointer.
+    ///
+    /// The caller becomes responsible for the memory previously managed by the `KBox`; in
+    /// particular, the caller must eventually call [`KBox::from_raw`] on it exactly once, or the
+    /// allocation (and the `T` in it) will leak.
+    pub fn into_raw(this: Self) -> NonNull<T> {
+        let ptr = this.ptr;
+        core::mem::forget(this);
+        ptr
+    }
+
+    /// Reconstructs a [`KBox`] from a raw pointer previously returned by [`KBox::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been obtained from [`KBox::into_raw`] on this same allocator `A`, and must
+    /// not be used again (by this or any other means) after this call.
+    pub unsafe fn from_raw(ptr: NonNull<T>) -> Self {
+        Self {
+            ptr,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Allocator> Deref for KBox<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: `self.ptr` always points at a live, initialized `T` for the lifetime of `self`.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, A: Allocator> DerefMut for KBox<T, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: same as `deref`, and `&mut self` gives us exclusive access to `*self.ptr`.
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T, A: Allocator> Drop for KBox<T, A> {
+    fn drop(&mut self) {
+        // SAFETY: `self.ptr` holds a live `T` allocated by `A` in `try_new`/`from_raw`, and is
+        // not used again after this call.
+        unsafe {
+            self.ptr.as_ptr().drop_in_place();
+            A::free(self.ptr.cast());
+        }
+    }
+}
@@ -0,0 +1,15 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+// The assembly template for `arch_static_branch!` on aarch64, mirroring
+// `arch/arm64/include/asm/jump_label.h`: a `nop` that `static_key` patching can turn into an
+// unconditional branch to `{label}`, plus a `__jump_table` entry recording the patch site, the
+// target and the key field (offset `{1}` into the key at `{0}`, with the initial branch direction
+// encoded via `{2}`).
+"1: nop",
+".pushsection __jump_table, \"aw\"",
+".align 3",
+".long 1b - ., {label} - .",
+".quad (({0} + {1}) + {2}) - .",
+".popsection",
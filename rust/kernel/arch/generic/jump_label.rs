@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Architecture-agnostic Rust implementation of jump_label.h.
+//!
+//! Unlike the x86 and aarch64 backends, this does not patch an inline branch at runtime; it
+//! simply reads the `static_key`'s enabled count on every call. This keeps code that uses static
+//! keys buildable on architectures without a dedicated `arch_static_branch!` backend, at the cost
+//! of the out-of-line fast path that patching provides.
+
+/// Generic, non-patching implementation of arch_static_branch
+#[macro_export]
+macro_rules! arch_static_branch {
+    ($key:path, $keytyp:ty, $field:ident, $branch:expr) => {{
+        // Accept the same operand contract as the patching backends (the field within the key
+        // and the initial branch direction). The initial branch direction is only used to build
+        // a `__jump_table` entry, which a non-patching implementation does not have; the field is
+        // still used below to locate the `static_key` inside `$key`.
+        let _ = ::core::mem::offset_of!($keytyp, $field);
+        let _ = $crate::arch::bool_to_int($branch);
+
+        // SAFETY: `$key` is a valid, initialized `$keytyp` whose `$field` is a `static_key`.
+        unsafe {
+            let key: *const $keytyp = ::core::ptr::addr_of!($key);
+            let key = ::core::ptr::addr_of!((*key).$field).cast::<$crate::bindings::static_key>();
+            $crate::bindings::static_key_count(key.cast_mut()) > 0
+        }
+    }};
+}
+
+pub use arch_static_branch;
@@ -0,0 +1,5 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Architecture-agnostic fallback code, used for architectures without a dedicated backend.
+
+pub mod jump_label;
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Architecture-specific code.
+//!
+//! The actual backend used for each item here is selected based on `target_arch`, with a generic
+//! (non-patching) fallback for architectures that do not have a dedicated implementation.
+
+#[cfg(target_arch = "x86_64")]
+#[path = "x86/mod.rs"]
+mod arch_impl;
+#[cfg(target_arch = "aarch64")]
+#[path = "aarch64/mod.rs"]
+mod arch_impl;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[path = "generic/mod.rs"]
+mod arch_impl;
+
+pub use arch_impl::jump_label;
+
+/// Converts a `bool` into the `0`/`1` integer operand expected by the `arch_static_branch!`
+/// backends.
+///
+/// This exists because `const` operands in [`core::arch::asm!`] cannot be a `bool` directly.
+#[doc(hidden)]
+pub const fn bool_to_int(branch: bool) -> i32 {
+    branch as i32
+}
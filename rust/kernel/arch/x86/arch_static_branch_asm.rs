@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+// The assembly template for `arch_static_branch!` on x86_64, mirroring
+// `arch/x86/include/asm/jump_label.h`: a 5-byte `nop` that `static_key` patching can turn into an
+// unconditional jump to `{label}`, plus a `__jump_table` entry recording the patch site, the
+// target and the key field (offset `{1}` into the key at `{0}`, with the initial branch direction
+// encoded via `{2}`).
+"1:",
+".byte 0x0f, 0x1f, 0x44, 0x00, 0x00",
+".pushsection __jump_table, \"aw\"",
+".balign 8",
+".long 1b - ., {label} - .",
+".quad (({0} + {1}) + {2}) - .",
+".popsection",
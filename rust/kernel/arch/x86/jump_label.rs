@@ -10,7 +10,7 @@
 macro_rules! arch_static_branch {
     ($key:path, $keytyp:ty, $field:ident, $branch:expr) => {'my_label: {
         core::arch::asm!(
-            include!(concat!(env!("SRCTREE"), "/rust/kernel/arch_static_branch_asm.rs")),
+            include!(concat!(env!("SRCTREE"), "/rust/kernel/arch/x86/arch_static_branch_asm.rs")),
             label {
                 break 'my_label true;
             },
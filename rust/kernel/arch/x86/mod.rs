@@ -0,0 +1,5 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! X86 architecture-specific code.
+
+pub mod jump_label;
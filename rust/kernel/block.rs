@@ -0,0 +1,354 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Block device driver abstractions, built on top of the blk-mq multi-queue request interface.
+//!
+//! C header: [`include/linux/blk-mq.h`](../../../../include/linux/blk-mq.h)
+//! C header: [`include/linux/blkdev.h`](../../../../include/linux/blkdev.h)
+
+use crate::{
+    alloc::{flags::GFP_KERNEL, KBox, Kmalloc},
+    bindings,
+    error::Error,
+    str::CStr,
+    Result,
+};
+use alloc::boxed::Box;
+use core::{marker::PhantomData, ptr::NonNull};
+
+/// Operations implemented by a block device driver.
+///
+/// `queue_rq` is handed ownership of the [`Request`] that was dispatched to the driver; it is
+/// free to complete it inline, or to stash it away (e.g. to hand off to hardware and complete
+/// from an interrupt handler later). Either way, [`Request::complete`] can only ever be called
+/// once per request, because completing a request consumes it.
+pub trait Operations: Sized {
+    /// Called by the block layer to dispatch a request to the driver for processing.
+    fn queue_rq(&self, rq: Request<Self>) -> Result;
+
+    /// Called to notify the driver that a previously quiesced queue has work again.
+    ///
+    /// Most drivers that process requests synchronously in [`queue_rq`](Operations::queue_rq)
+    /// do not need to override this.
+    fn commit_rqs(&self) {}
+}
+
+/// A handle to an in-flight block I/O request.
+///
+/// There is deliberately no [`Clone`]/[`Copy`] impl: a [`Request`] is handed to
+/// [`Operations::queue_rq`] by value and can only be finished by passing it (again by value) to
+/// [`Request::complete`], which ensures a driver cannot complete the same request twice.
+pub struct Request<T: Operations> {
+    ptr: NonNull<bindings::request>,
+    _p: PhantomData<T>,
+}
+
+impl<T: Operations> Request<T> {
+    /// Creates a [`Request`] from a raw `struct request` pointer handed to us by blk-mq.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point at a valid, live `struct request` that has just been dispatched to this
+    /// driver and not yet completed or wrapped by another [`Request`].
+    unsafe fn from_raw(ptr: NonNull<bindings::request>) -> Self {
+        Self {
+            ptr,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the number of bytes this request transfers.
+    pub fn data_len(&self) -> u32 {
+        // SAFETY: `self.ptr` is valid for the lifetime of `self` by the type invariant.
+        unsafe { (*self.ptr.as_ptr()).__data_len }
+    }
+
+    /// Completes the request with the given status, consuming it.
+    ///
+    /// Since this takes `self` by value and [`Request`] is not [`Clone`]/[`Copy`], a given
+    /// request can only ever be completed once.
+    pub fn complete(self, result: Result) {
+        let status = if result.is_ok() {
+            bindings::BLK_STS_OK
+        } else {
+            bindings::BLK_STS_IOERR
+        };
+        // SAFETY: `self.ptr` is valid, and ownership of the request is being given up by `self`
+        // being consumed here, matching what `blk_mq_end_request` expects.
+        unsafe { bindings::blk_mq_end_request(self.ptr.as_ptr(), status as _) };
+    }
+}
+
+/// The `blk_mq_ops::queue_rq` trampoline for a given [`Operations`] implementer.
+///
+/// # Safety
+///
+/// `hctx` must be a valid `blk_mq_hw_ctx` whose tag set's `driver_data` was set by
+/// [`GenDiskBuilder::build`] to a `KBox<T>`, and `bd` must be a valid, non-null
+/// `blk_mq_queue_data` with a live, not-yet-completed `request` in it.
+unsafe extern "C" fn queue_rq_callback<T: Operations>(
+    hctx: *mut bindings::blk_mq_hw_ctx,
+    bd: *const bindings::blk_mq_queue_data,
+) -> bindings::blk_status_t {
+    // SAFETY: Per this function's safety requirements, `hctx` is valid and its tag set's
+    // `driver_data` points at a live `T`.
+    let data = unsafe { (*(*hctx).queue).tag_set };
+    // SAFETY: `data` was set to a `KBox<T>::into_raw` pointer by `GenDiskBuilder::build` and
+    // outlives every call to this trampoline, since it is only freed in `GenDisk::drop`.
+    let ops = unsafe { &*((*data).driver_data.cast::<T>()) };
+    // SAFETY: Per this function's safety requirements, `bd` is valid and contains a live request.
+    let rq = unsafe { NonNull::new_unchecked((*bd).rq) };
+    // SAFETY: `rq` was just dispatched to this driver by blk-mq and has not been wrapped before.
+    let rq = unsafe { Request::from_raw(rq) };
+    match ops.queue_rq(rq) {
+        Ok(()) => bindings::BLK_STS_OK as _,
+        Err(_) => bindings::BLK_STS_IOERR as _,
+    }
+}
+
+/// The `blk_mq_ops::commit_rqs` trampoline for a given [`Operations`] implementer.
+///
+/// # Safety
+///
+/// Same as [`queue_rq_callback`].
+unsafe extern "C" fn commit_rqs_callback<T: Operations>(hctx: *mut bindings::blk_mq_hw_ctx) {
+    // SAFETY: See `queue_rq_callback`.
+    let data = unsafe { (*(*hctx).queue).tag_set };
+    // SAFETY: See `queue_rq_callback`.
+    let ops = unsafe { &*((*data).driver_data.cast::<T>()) };
+    ops.commit_rqs();
+}
+
+/// Builds the `blk_mq_ops` vtable for a given [`Operations`] implementer.
+///
+/// Every field is a trampoline that recovers `&T` from the tag set's `driver_data` and forwards
+/// to the corresponding [`Operations`] method; see `queue_rq_callback`/`commit_rqs_callback`.
+fn operations_vtable<T: Operations>() -> bindings::blk_mq_ops {
+    // SAFETY: Zero is a valid value for `blk_mq_ops`: every field not explicitly set below is a
+    // `None` (all-zero) function pointer, which blk-mq treats as "not implemented".
+    let mut ops: bindings::blk_mq_ops = unsafe { core::mem::zeroed() };
+    ops.queue_rq = Some(queue_rq_callback::<T>);
+    ops.commit_rqs = Some(commit_rqs_callback::<T>);
+    ops
+}
+
+/// A builder for a [`GenDisk`].
+///
+/// # Examples
+///
+/// ```ignore
+/// let disk = GenDiskBuilder::new()
+///     .logical_block_size(512)
+///     .physical_block_size(4096)
+///     .capacity_sectors(1 << 20)
+///     .queue_depth(128)
+///     .build(c_str!("mydisk"), MyDriver::new())?;
+/// ```
+pub struct GenDiskBuilder {
+    logical_block_size: u32,
+    physical_block_size: u32,
+    capacity_sectors: u64,
+    queue_depth: u32,
+}
+
+impl Default for GenDiskBuilder {
+    fn default() -> Self {
+        Self {
+            logical_block_size: 512,
+            physical_block_size: 512,
+            capacity_sectors: 0,
+            queue_depth: 128,
+        }
+    }
+}
+
+impl GenDiskBuilder {
+    /// Creates a new builder with the kernel's usual defaults (512-byte blocks, depth 128, empty
+    /// capacity).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the logical block size, in bytes.
+    pub fn logical_block_size(mut self, size: u32) -> Self {
+        self.logical_block_size = size;
+        self
+    }
+
+    /// Sets the physical block size, in bytes.
+    pub fn physical_block_size(mut self, size: u32) -> Self {
+        self.physical_block_size = size;
+        self
+    }
+
+    /// Sets the disk's capacity, in 512-byte sectors.
+    pub fn capacity_sectors(mut self, sectors: u64) -> Self {
+        self.capacity_sectors = sectors;
+        self
+    }
+
+    /// Sets the number of in-flight requests the hardware queue can hold.
+    pub fn queue_depth(mut self, depth: u32) -> Self {
+        self.queue_depth = depth;
+        self
+    }
+
+    /// Allocates the `blk_mq_tag_set`, builds the `struct gendisk` and registers it under `name`,
+    /// handing ownership of both (and of `ops`) to the returned [`GenDisk`].
+    pub fn build<T: Operations>(self, name: &CStr, ops: T) -> Result<GenDisk<T>> {
+        let driver_data: KBox<T, Kmalloc> = KBox::try_new(ops, GFP_KERNEL)?;
+        let vtable: KBox<bindings::blk_mq_ops, Kmalloc> =
+            KBox::try_new(operations_vtable::<T>(), GFP_KERNEL)?;
+
+        // SAFETY: Zero is a valid initial value for `blk_mq_tag_set`; every field used by
+        // `blk_mq_alloc_tag_set` is explicitly set below.
+        let mut tagset: Box<bindings::blk_mq_tag_set> =
+            unsafe { Box::try_new(core::mem::zeroed()).map_err(|_| Error::ENOMEM)? };
+        tagset.ops = KBox::into_raw(vtable).as_ptr() as _;
+        tagset.queue_depth = self.queue_depth;
+        tagset.numa_node = bindings::NUMA_NO_NODE as _;
+        tagset.driver_data = KBox::into_raw(driver_data).as_ptr().cast();
+
+        // SAFETY: `tagset` has just been filled in above with a valid `ops` vtable and
+        // `driver_data`.
+        let ret = unsafe { bindings::blk_mq_alloc_tag_set(tagset.as_mut()) };
+        if ret != 0 {
+            // SAFETY: `tagset.driver_data`/`tagset.ops` were set from `KBox::into_raw` above and
+            // have not been freed or handed to a `GenDisk` yet, since allocation failed.
+            unsafe { Self::free_driver_data_and_vtable::<T>(&tagset) };
+            return Err(Error::from_errno(ret));
+        }
+
+        // SAFETY: `tagset` was just successfully initialized above, and stays alive for at least
+        // as long as `gendisk`, because both are owned by the returned `GenDisk`.
+        let gendisk = unsafe { bindings::blk_mq_alloc_disk(tagset.as_mut(), core::ptr::null_mut()) };
+        let gendisk = match NonNull::new(gendisk) {
+            Some(g) => g,
+            None => {
+                // SAFETY: `tagset` was successfully allocated above and is being torn down since
+                // disk allocation failed.
+                unsafe {
+                    bindings::blk_mq_free_tag_set(tagset.as_mut());
+                    Self::free_driver_data_and_vtable::<T>(&tagset);
+                }
+                return Err(Error::ENOMEM);
+            }
+        };
+
+        // SAFETY: `gendisk` was just allocated above and is exclusively owned here.
+        unsafe {
+            let queue = (*gendisk.as_ptr()).queue;
+            bindings::blk_queue_logical_block_size(queue, self.logical_block_size);
+            bindings::blk_queue_physical_block_size(queue, self.physical_block_size);
+            bindings::set_capacity(gendisk.as_ptr(), self.capacity_sectors);
+        }
+
+        // SAFETY: `gendisk` was just allocated above and is exclusively owned here.
+        let disk_name = unsafe { &mut (*gendisk.as_ptr()).disk_name };
+        let name = name.as_bytes_with_nul();
+        if name.len() > disk_name.len() {
+            // SAFETY: `gendisk`/`tagset` were successfully allocated above and are being torn
+            // down since the name does not fit into `disk_name`.
+            unsafe {
+                bindings::put_disk(gendisk.as_ptr());
+                bindings::blk_mq_free_tag_set(tagset.as_mut());
+                Self::free_driver_data_and_vtable::<T>(&tagset);
+            }
+            return Err(Error::EINVAL);
+        }
+        for (dst, src) in disk_name.iter_mut().zip(name) {
+            *dst = *src as _;
+        }
+
+        // SAFETY: `gendisk` is fully configured above, including its `disk_name`.
+        let ret = unsafe {
+            bindings::device_add_disk(
+                core::ptr::null_mut(),
+                gendisk.as_ptr(),
+                core::ptr::null_mut(),
+            )
+        };
+        if ret != 0 {
+            // SAFETY: `gendisk`/`tagset` were successfully allocated above and are being torn
+            // down since registration failed.
+            unsafe {
+                bindings::put_disk(gendisk.as_ptr());
+                bindings::blk_mq_free_tag_set(tagset.as_mut());
+                Self::free_driver_data_and_vtable::<T>(&tagset);
+            }
+            return Err(Error::from_errno(ret));
+        }
+
+        Ok(GenDisk {
+            gendisk,
+            tagset,
+            _p: PhantomData,
+        })
+    }
+
+    /// Reconstructs and drops the `KBox<T>` and `KBox<blk_mq_ops>` leaked into `tagset` by
+    /// [`GenDiskBuilder::build`], for use on every error path that returns before handing
+    /// `tagset` off to a [`GenDisk`] (whose own `Drop` impl does the equivalent on the success
+    /// path).
+    ///
+    /// # Safety
+    ///
+    /// `tagset.driver_data`/`tagset.ops` must still be the untouched `KBox::into_raw` pointers set
+    /// earlier in [`GenDiskBuilder::build`], and must not be read or freed again after this call.
+    unsafe fn free_driver_data_and_vtable<T: Operations>(tagset: &bindings::blk_mq_tag_set) {
+        // SAFETY: Per this function's safety requirements, `tagset.driver_data` is still the
+        // `KBox<T, Kmalloc>` pointer leaked in `build`, and is not used again after this call.
+        unsafe {
+            drop(KBox::<T, Kmalloc>::from_raw(NonNull::new_unchecked(
+                tagset.driver_data.cast::<T>(),
+            )))
+        };
+        // SAFETY: Per this function's safety requirements, `tagset.ops` is still the
+        // `KBox<blk_mq_ops, Kmalloc>` pointer leaked in `build`, and is not used again after this
+        // call.
+        unsafe {
+            drop(KBox::<bindings::blk_mq_ops, Kmalloc>::from_raw(
+                NonNull::new_unchecked(tagset.ops.cast_mut()),
+            ))
+        };
+    }
+}
+
+/// A registered block device, owning the `blk_mq_tag_set` and `struct gendisk` that back it.
+///
+/// Both are torn down, in reverse order of creation, when this value is dropped.
+pub struct GenDisk<T: Operations> {
+    gendisk: NonNull<bindings::gendisk>,
+    tagset: Box<bindings::blk_mq_tag_set>,
+    _p: PhantomData<T>,
+}
+
+// SAFETY: A `GenDisk` does not provide any interior mutability across threads that isn't already
+// synchronized by the block layer itself.
+unsafe impl<T: Operations> Send for GenDisk<T> {}
+// SAFETY: See above.
+unsafe impl<T: Operations> Sync for GenDisk<T> {}
+
+impl<T: Operations> Drop for GenDisk<T> {
+    fn drop(&mut self) {
+        // SAFETY: `self.gendisk` was registered in `GenDiskBuilder::build` and has not been torn
+        // down yet, since this is the only place that does so.
+        unsafe {
+            bindings::del_gendisk(self.gendisk.as_ptr());
+            bindings::put_disk(self.gendisk.as_ptr());
+        }
+        let driver_data = self.tagset.driver_data.cast::<T>();
+        // SAFETY: `self.tagset` was allocated in `GenDiskBuilder::build`, which is the only place
+        // that ever sets `driver_data`, to a `KBox<T, Kmalloc>` that has not been freed yet.
+        unsafe { drop(KBox::<T, Kmalloc>::from_raw(NonNull::new_unchecked(driver_data))) };
+        // SAFETY: `self.tagset.ops` was leaked from a `KBox` in `GenDiskBuilder::build` and is not
+        // referenced anywhere else once the disk using it has been torn down above.
+        unsafe {
+            drop(KBox::<bindings::blk_mq_ops, Kmalloc>::from_raw(
+                NonNull::new_unchecked(self.tagset.ops.cast_mut()),
+            ))
+        };
+        // SAFETY: `self.tagset` was successfully initialized in `GenDiskBuilder::build` and the
+        // disk using it has just been torn down above.
+        unsafe { bindings::blk_mq_free_tag_set(self.tagset.as_mut()) };
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Generic devices that are part of the kernel's driver model.
+//!
+//! C header: [`include/linux/device.h`](../../../../include/linux/device.h)
+
+use crate::{
+    bindings,
+    str::CStr,
+    types::{ARef, AlwaysRefCounted},
+};
+use core::ptr::NonNull;
+
+/// A reference-counted kernel `struct device`.
+///
+/// Instances are always reference-counted: an [`ARef<Device>`] bumps the refcount via
+/// `get_device` on [`Clone`] and drops it via `put_device` on [`Drop`], exactly like other
+/// [`AlwaysRefCounted`] wrappers in this crate.
+#[repr(transparent)]
+pub struct Device(bindings::device);
+
+impl Device {
+    /// Creates a new [`ARef<Device>`] from a raw `struct device` pointer, taking a new reference.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point at a valid, live `struct device` for the duration of this call.
+    pub unsafe fn from_raw(ptr: *mut bindings::device) -> ARef<Self> {
+        // SAFETY: `ptr` is valid by the caller's safety requirement, and `Device` is a
+        // `#[repr(transparent)]` wrapper around `bindings::device`.
+        let dev = unsafe { &*ptr.cast::<Self>() };
+        dev.into()
+    }
+
+    /// Returns the raw `struct device` pointer backing this [`Device`].
+    ///
+    /// # Safety
+    ///
+    /// The caller may not use the returned pointer to free the device, nor let it outlive `self`.
+    pub fn as_raw(&self) -> *mut bindings::device {
+        (&self.0 as *const bindings::device).cast_mut()
+    }
+}
+
+// SAFETY: The type invariants of `Device` (it only ever exists as a `struct device` that is
+// reference-counted by the C side) are exactly what `get_device`/`put_device` require.
+unsafe impl AlwaysRefCounted for Device {
+    fn inc_ref(&self) {
+        // SAFETY: The existence of a shared reference guarantees that the refcount is non-zero.
+        unsafe { bindings::get_device(self.as_raw()) };
+    }
+
+    unsafe fn dec_ref(obj: NonNull<Self>) {
+        // SAFETY: The safety requirements of `dec_ref` guarantee that there is an outstanding
+        // reference to release.
+        unsafe { bindings::put_device(obj.as_ref().as_raw()) };
+    }
+}
+
+// SAFETY: `Device` just wraps a kernel `struct device` pointer, which the C side allows passing
+// between threads.
+unsafe impl Send for Device {}
+// SAFETY: All the `Device` methods that read/modify the C side either only read immutable state
+// or go through APIs that are themselves thread-safe.
+unsafe impl Sync for Device {}
+
+/// Formats `args` and passes the result to `dev_printk`, attributed to `dev` at `level`.
+///
+/// `level` is one of the kernel's `KERN_*` prefixes (e.g. `"\x013"` for `KERN_ERR`). Not meant to
+/// be called directly; use the [`dev_emerg!`], [`dev_err!`], [`dev_warn!`] and [`dev_info!`]
+/// macros instead, which build `level`/`args` for you, mirroring the `pr_*!` family in
+/// [`print`](crate::print).
+#[doc(hidden)]
+pub fn dev_printk(dev: &Device, level: &CStr, args: core::fmt::Arguments<'_>) {
+    // SAFETY: `dev_printk` reads `level`/`fmt` and `&args` only for the duration of this call,
+    // `level`/`fmt` are both valid NUL-terminated strings, and `dev.as_raw()` is a valid device
+    // pointer for at least that long because `dev` is borrowed for the whole call.
+    unsafe {
+        bindings::dev_printk(
+            level.as_char_ptr(),
+            dev.as_raw(),
+            c_str!("%pA\0").as_char_ptr(),
+            &args as *const _ as *const core::ffi::c_void,
+        );
+    }
+}
+
+/// Prints an emergency-level message (level 0) prefixed with the device name.
+#[macro_export]
+macro_rules! dev_emerg {
+    ($dev:expr, $($f:tt)*) => {
+        $crate::device::dev_printk($dev, c_str!("\x010"), ::core::format_args!($($f)*))
+    };
+}
+
+/// Prints an error-level message (level 3) prefixed with the device name.
+#[macro_export]
+macro_rules! dev_err {
+    ($dev:expr, $($f:tt)*) => {
+        $crate::device::dev_printk($dev, c_str!("\x013"), ::core::format_args!($($f)*))
+    };
+}
+
+/// Prints a warning-level message (level 4) prefixed with the device name.
+#[macro_export]
+macro_rules! dev_warn {
+    ($dev:expr, $($f:tt)*) => {
+        $crate::device::dev_printk($dev, c_str!("\x014"), ::core::format_args!($($f)*))
+    };
+}
+
+/// Prints an info-level message (level 6) prefixed with the device name.
+#[macro_export]
+macro_rules! dev_info {
+    ($dev:expr, $($f:tt)*) => {
+        $crate::device::dev_printk($dev, c_str!("\x016"), ::core::format_args!($($f)*))
+    };
+}
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Firmware abstraction.
+//!
+//! C header: [`include/linux/firmware.h`](../../../../include/linux/firmware.h)
+
+use crate::{bindings, device::Device, error::Error, str::CStr, Result};
+use core::{marker::PhantomData, ptr::NonNull};
+
+/// A firmware blob loaded via `request_firmware`.
+///
+/// Borrows the [`Device`] it was requested against, so a [`Firmware`] cannot outlive the device
+/// used to load it. The underlying `struct firmware` is released (exactly once) when the
+/// [`Firmware`] is dropped.
+pub struct Firmware<'a> {
+    ptr: NonNull<bindings::firmware>,
+    _dev: PhantomData<&'a Device>,
+}
+
+impl<'a> Firmware<'a> {
+    /// Requests the firmware blob named `name`, logging an error to the kernel log if it cannot
+    /// be found.
+    pub fn request(name: &CStr, dev: &'a Device) -> Result<Self> {
+        Self::request_with(name, dev, bindings::request_firmware)
+    }
+
+    /// Requests the firmware blob named `name`, without logging if it cannot be found.
+    ///
+    /// Useful for optional firmware, where a missing file is an expected outcome rather than a
+    /// driver misconfiguration.
+    pub fn request_nowarn(name: &CStr, dev: &'a Device) -> Result<Self> {
+        Self::request_with(name, dev, bindings::request_firmware_nowarn)
+    }
+
+    fn request_with(
+        name: &CStr,
+        dev: &'a Device,
+        request: unsafe extern "C" fn(
+            *mut *const bindings::firmware,
+            *const core::ffi::c_char,
+            *mut bindings::device,
+        ) -> core::ffi::c_int,
+    ) -> Result<Self> {
+        let mut ptr: *const bindings::firmware = core::ptr::null();
+        // SAFETY: `&mut ptr` is a valid out-pointer, `name` is a valid NUL-terminated string for
+        // the duration of this call, and `dev.as_raw()` is a valid device pointer because `dev`
+        // outlives this call.
+        let ret = unsafe { request(&mut ptr, name.as_char_ptr(), dev.as_raw()) };
+        if ret != 0 {
+            return Err(Error::from_errno(ret));
+        }
+        // SAFETY: `request` returned success, so `ptr` now points at a valid, live `firmware`.
+        let ptr = unsafe { NonNull::new_unchecked(ptr.cast_mut()) };
+        Ok(Self {
+            ptr,
+            _dev: PhantomData,
+        })
+    }
+
+    /// Returns the firmware blob's contents.
+    pub fn data(&self) -> &[u8] {
+        // SAFETY: `self.ptr` is valid for the lifetime of `self`, and `data`/`size` describe a
+        // byte buffer that is live for as long as the `firmware` itself.
+        unsafe {
+            let f = self.ptr.as_ptr();
+            core::slice::from_raw_parts((*f).data, (*f).size as usize)
+        }
+    }
+
+    /// Returns the size, in bytes, of the firmware blob.
+    pub fn size(&self) -> usize {
+        self.data().len()
+    }
+}
+
+impl Drop for Firmware<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `self.ptr` was obtained from a successful `request_firmware`/
+        // `request_firmware_nowarn` call and has not been released before.
+        unsafe { bindings::release_firmware(self.ptr.as_ptr()) };
+    }
+}
@@ -193,6 +193,67 @@
 //! For more information on how to use [`pin_init_from_closure()`], take a look at the uses inside
 //! the `kernel` crate. The [`sync`] module is a good starting point.
 //!
+//! ## Fields are dropped in reverse declaration order
+//!
+//! When a [`pin_init!`]/[`init!`] initializer fails partway through, the fields that have already
+//! been initialized are dropped before the error is propagated. This happens in exactly the
+//! reverse of the order the fields are written in the initializer (i.e. the last field to
+//! initialize successfully is the first one dropped). This is a stable guarantee, not an
+//! implementation detail: it is the same order in which local variables are dropped (note that a
+//! fully initialized struct drops its fields in declaration order instead), and lets a field's
+//! destructor safely assume, while running, that every field written before it in the
+//! initializer is still live.
+//!
+//! If you are relying on this order (for example, a field that registers itself with an earlier
+//! field and must unregister before that field is dropped), you can pin it down with a
+//! `#[drop_order(a, b, ..)]` attribute on the initializer, listing its fields in the order the
+//! initializer writes them. It does not change any behavior by itself; it is checked at compile
+//! time against the initializer's actual field order, so that if someone later reorders the fields
+//! without updating the attribute, the build fails instead of silently changing the drop order a
+//! caller depends on.
+//!
+//! ```rust,ignore
+//! # use kernel::sync::{new_mutex, Mutex};
+//! #[pin_data]
+//! struct Linked {
+//!     #[pin]
+//!     parent: Mutex<usize>,
+//!     #[pin]
+//!     child: Mutex<usize>,
+//! }
+//!
+//! let linked = pin_init!(#[drop_order(parent, child)] Linked {
+//!     parent <- new_mutex!(0, "Linked::parent"),
+//!     child <- new_mutex!(0, "Linked::child"),
+//! });
+//! ```
+//!
+//! ## Initializing array fields in place
+//!
+//! Writing `field <- $val` does not require `$val` to build its result on the stack before moving
+//! it into place: `$val` is only ever handed the raw destination pointer (via
+//! [`Init::__init`]/[`PinInit::__init`]) and is free to write directly into it. [`init_array_from_fn`]
+//! and its siblings ([`pin_init_array_from_fn`], [`try_init_array_from_fn`],
+//! [`try_pin_init_array_from_fn`]) make use of exactly this: they initialize a `[T; N]` field
+//! element-by-element straight into the destination, keeping a running guard over the
+//! already-initialized prefix `[0, i)` that is dropped if a later element fails, and dismissed once
+//! all `N` elements succeed. This means large kernel buffers can be initialized in place without
+//! ever materializing the whole array on the stack:
+//!
+//! ```rust
+//! # #![allow(clippy::disallowed_names)]
+//! use kernel::{init::pin_init_array_from_fn, sync::{new_mutex, Mutex}};
+//! #[pin_data]
+//! struct Foo {
+//!     #[pin]
+//!     buf: [Mutex<usize>; 1_000],
+//! }
+//!
+//! let foo = pin_init!(Foo {
+//!     buf <- pin_init_array_from_fn(|i| new_mutex!(i, "Foo::buf")),
+//! });
+//! ```
+//!
 //! [`sync`]: kernel::sync
 //! [pinning]: https://doc.rust-lang.org/std/pin/index.html
 //! [structurally pinned fields]:
@@ -303,6 +364,194 @@ where
     unsafe { pin_init_from_closure(init) }
 }
 
+/// Initializes an array by initializing each element via the provided initializer, short-
+/// circuiting if `make_init` itself fails to produce an initializer for some index.
+///
+/// Like [`init_array_from_fn`], but `make_init` may fail before ever producing an initializer
+/// (e.g. because building it requires a fallible lookup or allocation); on such a failure, the
+/// elements already initialized are dropped before the error is returned.
+///
+/// # Examples
+///
+/// ```rust
+/// use kernel::{error::Error, init::try_init_array_from_fn};
+/// let array: Box<[usize; 1_000]> =
+///     Box::init::<Error>(try_init_array_from_fn(|i| Ok(i))).unwrap();
+/// assert_eq!(array.len(), 1_000);
+/// ```
+pub fn try_init_array_from_fn<I, const N: usize, T, E>(
+    mut make_init: impl FnMut(usize) -> core::result::Result<I, E>,
+) -> impl Init<[T; N], E>
+where
+    I: Init<T, E>,
+{
+    let init = move |slot: *mut [T; N]| {
+        let slot = slot.cast::<T>();
+        // Counts the number of initialized elements and when dropped drops that many elements from
+        // `slot`.
+        let mut init_count = ScopeGuard::new_with_data(0, |i| {
+            // We now free every element that has been initialized before.
+            // SAFETY: The loop initialized exactly the values from 0..i and since we
+            // return `Err` below, the caller will consider the memory at `slot` as
+            // uninitialized.
+            unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(slot, i)) };
+        });
+        for i in 0..N {
+            let init = make_init(i)?;
+            // SAFETY: Since 0 <= `i` < N, it is still in bounds of `[T; N]`.
+            let ptr = unsafe { slot.add(i) };
+            // SAFETY: The pointer is derived from `slot` and thus satisfies the `__init`
+            // requirements.
+            unsafe { init.__init(ptr) }?;
+            *init_count += 1;
+        }
+        init_count.dismiss();
+        Ok(())
+    };
+    // SAFETY: The initializer above initializes every element of the array. On failure (be it from
+    // `make_init` or from the produced initializer) it drops any initialized elements and returns
+    // `Err`.
+    unsafe { init_from_closure(init) }
+}
+
+/// Initializes an array by initializing each element via the provided initializer, short-
+/// circuiting if `make_init` itself fails to produce an initializer for some index.
+///
+/// Pinned counterpart of [`try_init_array_from_fn`]; see it for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use kernel::{sync::{Arc, Mutex}, init::try_pin_init_array_from_fn, new_mutex};
+/// let array: Arc<[Mutex<usize>; 1_000]> =
+///     Arc::pin_init(try_pin_init_array_from_fn(|i| Ok(new_mutex!(i)))).unwrap();
+/// assert_eq!(array.len(), 1_000);
+/// ```
+pub fn try_pin_init_array_from_fn<I, const N: usize, T, E>(
+    mut make_init: impl FnMut(usize) -> core::result::Result<I, E>,
+) -> impl PinInit<[T; N], E>
+where
+    I: PinInit<T, E>,
+{
+    let init = move |slot: *mut [T; N]| {
+        let slot = slot.cast::<T>();
+        // Counts the number of initialized elements and when dropped drops that many elements from
+        // `slot`.
+        let mut init_count = ScopeGuard::new_with_data(0, |i| {
+            // SAFETY: see `try_init_array_from_fn`.
+            unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(slot, i)) };
+        });
+        for i in 0..N {
+            let init = make_init(i)?;
+            // SAFETY: Since 0 <= `i` < N, it is still in bounds of `[T; N]`.
+            let ptr = unsafe { slot.add(i) };
+            // SAFETY: The pointer is derived from `slot` and thus satisfies the `__pinned_init`
+            // requirements.
+            unsafe { init.__pinned_init(ptr) }?;
+            *init_count += 1;
+        }
+        init_count.dismiss();
+        Ok(())
+    };
+    // SAFETY: The initializer above initializes every element of the array. On failure (be it from
+    // `make_init` or from the produced initializer) it drops any initialized elements and returns
+    // `Err`.
+    unsafe { pin_init_from_closure(init) }
+}
+
+/// Initializes a boxed slice of runtime-known length by initializing each element via the
+/// provided initializer.
+///
+/// Like [`init_array_from_fn`], but `len` is only known at runtime (e.g. it depends on the number
+/// of CPUs or the size of a queue configured at probe time), so the result is a `Box<[T]>`
+/// instead of a `[T; N]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use kernel::{error::Error, init::init_slice_from_fn};
+/// let len = 1_000;
+/// let slice: Box<[usize]> = init_slice_from_fn::<_, _, Error>(len, |i| i).unwrap();
+/// assert_eq!(slice.len(), len);
+/// ```
+pub fn init_slice_from_fn<I, T, E>(
+    len: usize,
+    mut make_init: impl FnMut(usize) -> I,
+) -> core::result::Result<Box<[T]>, E>
+where
+    I: Init<T, E>,
+    E: From<AllocError>,
+{
+    let mut this = Box::try_new_uninit_slice(len)?;
+    let slot = this.as_mut_ptr().cast::<T>();
+    // Counts the number of initialized elements and when dropped drops that many elements from
+    // `slot`.
+    let mut init_count = ScopeGuard::new_with_data(0, |i| {
+        // SAFETY: The loop initialized exactly the values from 0..i and since we return `Err`
+        // below, `this` (and thus `slot`) is dropped without running `T`'s destructor.
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(slot, i)) };
+    });
+    for i in 0..len {
+        let init = make_init(i);
+        // SAFETY: Since 0 <= `i` < `len`, it is still in bounds of `slot`.
+        let ptr = unsafe { slot.add(i) };
+        // SAFETY: The pointer is derived from `slot` and thus satisfies the `__init` requirements.
+        unsafe { init.__init(ptr) }?;
+        *init_count += 1;
+    }
+    init_count.dismiss();
+    // SAFETY: Every element of `this` has been initialized above.
+    Ok(unsafe { this.assume_init() })
+}
+
+/// Initializes a boxed slice of runtime-known length by initializing each element via the
+/// provided initializer.
+///
+/// Pinned counterpart of [`init_slice_from_fn`]; see it for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use kernel::{error::Error, sync::Mutex, init::pin_init_slice_from_fn, new_mutex};
+/// let len = 1_000;
+/// let slice: Pin<Box<[Mutex<usize>]>> =
+///     pin_init_slice_from_fn::<_, _, Error>(len, |i| new_mutex!(i)).unwrap();
+/// assert_eq!(slice.len(), len);
+/// ```
+pub fn pin_init_slice_from_fn<I, T, E>(
+    len: usize,
+    mut make_init: impl FnMut(usize) -> I,
+) -> core::result::Result<Pin<Box<[T]>>, E>
+where
+    I: PinInit<T, E>,
+    E: From<AllocError>,
+{
+    let mut this = Box::try_new_uninit_slice(len)?;
+    let slot = this.as_mut_ptr().cast::<T>();
+    // Counts the number of initialized elements and when dropped drops that many elements from
+    // `slot`.
+    let mut init_count = ScopeGuard::new_with_data(0, |i| {
+        // SAFETY: see `init_slice_from_fn`.
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(slot, i)) };
+    });
+    for i in 0..len {
+        let init = make_init(i);
+        // SAFETY: Since 0 <= `i` < `len`, it is still in bounds of `slot`.
+        let ptr = unsafe { slot.add(i) };
+        // SAFETY: The pointer is derived from `slot` and thus satisfies the `__pinned_init`
+        // requirements. The resulting `Box` is pinned below and never unpinned, so `T: !Unpin` is
+        // not moved afterwards.
+        unsafe { init.__pinned_init(ptr) }?;
+        *init_count += 1;
+    }
+    init_count.dismiss();
+    // SAFETY: Every element of `this` has been initialized above.
+    let this = unsafe { this.assume_init() };
+    // SAFETY: We never move out of `this` again: it is immediately wrapped in `Pin` and the
+    // caller cannot get a `&mut` to its contents without going through `Pin`'s API.
+    Ok(unsafe { Pin::new_unchecked(this) })
+}
+
 macro_rules! impl_zeroable {
     ($($({$($generics:tt)*})? $t:ty, )*) => {
         $(unsafe impl$($($generics)*)? Zeroable for $t {})*
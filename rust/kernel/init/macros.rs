@@ -508,6 +508,71 @@
 /// - `init_slot`: recursively creates the code that initializes all fields in `slot`.
 /// - `make_initializer`: recursively create the struct initializer that guarantees that every
 ///   field has been initialized exactly once.
+///
+/// The base case additionally has a form with an `@variant($variant:ident)` parameter (and no
+/// `@munch_fields`, so it bypasses `with_update_parsed`) for constructing a single variant of an
+/// enum in-place, in both the pinned (`pin_init!(MyEnum::V { .. })`) and the non-pinned
+/// (`init!(MyEnum::V { .. })`) data case; in that case `init_slot_variant` is used
+/// instead of `init_slot`, since an enum has no well-defined per-field addresses before its
+/// discriminant has been written:
+///
+/// ```rust,ignore
+/// # use kernel::init::*;
+/// # use kernel::sync::{new_mutex, Mutex};
+/// #[pin_data]
+/// enum Device {
+///     Idle,
+///     Running {
+///         #[pin]
+///         stats: Mutex<usize>,
+///         id: u32,
+///     },
+/// }
+///
+/// let running = pin_init!(Device::Running {
+///     stats <- new_mutex!(0, "Device::stats"),
+///     id: 1,
+/// });
+/// ```
+///
+/// (This can't be exercised as a real doctest yet: the front-end `pin_init!`/`init!` macros that
+/// parse a `MyEnum::Variant { .. }` path and forward an `@variant(..)` parameter down to this
+/// macro are not part of this crate, so there is currently no way to drive `init_slot_variant`
+/// end-to-end from here. `init_slot_variant` itself still has to build each field in its own
+/// `MaybeUninit` storage and move the whole variant into `slot` in one go, since unlike a struct
+/// field, there is no `addr_of_mut!((*slot).Variant.field)` place expression to project into an
+/// enum variant before its discriminant is written -- only `match`, which itself requires a valid
+/// discriminant to already be there.)
+/// - `tuple`: the tuple-struct counterpart of the base case, for `pin_init!(Wrapper(inner <-
+///   ..))`; fields are selected by tuple index (`init_slot_tuple`/`make_initializer_tuple`)
+///   instead of by identifier.
+/// - `unit`: for `pin_init!(UnitStruct)`; there are no fields at all, so the value is its own
+///   initializer.
+/// - `with_update_parsed` additionally has an `@base($($base:expr)?)` counterpart to `@zeroed`,
+///   for `Foo { field <- init, ..base }`: when present, `init_slot_base` is used instead of
+///   `init_slot`, since each mentioned field must first drop the value `base` left there before
+///   overwriting it (see `init_slot_base`'s own doc comment for the exact guarantee).
+///
+/// `init_slot` (and `make_initializer`) additionally accept `$field <-? $val` (mirroring the `<-?`
+/// syntax already supported by the `primitive_init` proc-macro initializer, see
+/// `rust/macros/primitive_init.rs`): the sub-initializer's own error type is converted into the
+/// outer `@error($err)` via `.map_err(::core::convert::Into::into)` before the `?`, so a single
+/// struct can mix sub-initializers with different error types without every call site doing the
+/// conversion by hand. Plain `<-` keeps requiring the sub-initializer's error type to already be
+/// `$err`. Currently only supported for named-field structs, not enum variants or tuple structs.
+/// - `ordered`: wraps the base case with an extra `@drop_order($($order:ident)*)` parameter
+///   coming from a `#[drop_order(a, b, ..)]` attribute on the initializer; it is a pure
+///   compile-time check (via `check_drop_order`/`assert_same_ident`, using `static_assert!` on
+///   `stringify!`ed field names) that `$order` restates the declared field order verbatim, then
+///   forwards to the base case unchanged. This exists so that
+///   [the drop-order guarantee](crate::init#fields-are-dropped-in-reverse-declaration-order) a
+///   caller is relying on stays enforced if the initializer's field order is later edited.
+///
+/// `[T; N]` fields do not get a dedicated `init_slot` arm: plain `field <- $val` already hands
+/// `$val` the raw destination pointer rather than requiring it to build a value on the stack
+/// first, so [`init_array_from_fn`](crate::init::init_array_from_fn) and its siblings can (and
+/// do) initialize such fields element-by-element directly in place. See
+/// [the array section](crate::init#initializing-array-fields-in-place) of `init.rs` for details.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __init_internal {
@@ -534,6 +599,7 @@ macro_rules! __init_internal {
             @has_data($has_data, $get_data),
             @construct_closure($construct_closure),
             @zeroed(), // Nothing means default behavior.
+            @base(),
         )
     };
     (
@@ -559,6 +625,36 @@ macro_rules! __init_internal {
             @has_data($has_data, $get_data),
             @construct_closure($construct_closure),
             @zeroed(()), // `()` means zero all fields not mentioned.
+            @base(),
+        )
+    };
+    // `Foo { field <- init, ..base }`: fields not mentioned come from an existing, owned `$t`
+    // instead of `Zeroable::zeroed()`. Must come after the literal `..Zeroable::zeroed()` arm
+    // above, since that is the more specific case.
+    (
+        @this($($this:ident)?),
+        @typ($t:path),
+        @fields($($fields:tt)*),
+        @error($err:ty),
+        // Either `PinData` or `InitData`, `$use_data` should only be present in the `PinData`
+        // case.
+        @data($data:ident, $($use_data:ident)?),
+        // `HasPinData` or `HasInitData`.
+        @has_data($has_data:ident, $get_data:ident),
+        // `pin_init_from_closure` or `init_from_closure`.
+        @construct_closure($construct_closure:ident),
+        @munch_fields(..$base:expr),
+    ) => {
+        $crate::__init_internal!(with_update_parsed:
+            @this($($this)?),
+            @typ($t),
+            @fields($($fields)*),
+            @error($err),
+            @data($data, $($use_data)?),
+            @has_data($has_data, $get_data),
+            @construct_closure($construct_closure),
+            @zeroed(),
+            @base($base),
         )
     };
     (
@@ -599,54 +695,456 @@ macro_rules! __init_internal {
         // `pin_init_from_closure` or `init_from_closure`.
         @construct_closure($construct_closure:ident),
         @zeroed($($init_zeroed:expr)?),
+        @base(),
+    ) => {{
+        // We do not want to allow arbitrary returns, so we declare this type as the `Ok` return
+        // type and shadow it later when we insert the arbitrary user code. That way there will be
+        // no possibility of returning without `unsafe`.
+        struct __InitOk;
+        // Get the data about fields from the supplied type.
+        let data = unsafe {
+            use $crate::init::__internal::$has_data;
+            // Here we abuse `paste!` to retokenize `$t`. Declarative macros have some internal
+            // information that is associated to already parsed fragments, so a path fragment
+            // cannot be used in this position. Doing the retokenization results in valid rust
+            // code.
+            ::kernel::macros::paste!($t::$get_data())
+        };
+        // Ensure that `data` really is of type `$data` and help with type inference:
+        let init = $crate::init::__internal::$data::make_closure::<_, __InitOk, $err>(
+            data,
+            move |slot| {
+                {
+                    // Shadow the structure so it cannot be used to return early.
+                    struct __InitOk;
+                    // If `$init_zeroed` is present we should zero the slot now and not emit an
+                    // error when fields are missing (since they will be zeroed). We also have to
+                    // check that the type actually implements `Zeroable`.
+                    $({
+                        fn assert_zeroable<T: $crate::init::Zeroable>(_: *mut T) {}
+                        // Ensure that the struct is indeed `Zeroable`.
+                        assert_zeroable(slot);
+                        // SAFETY: The type implements `Zeroable` by the check above.
+                        unsafe { ::core::ptr::write_bytes(slot, 0, 1) };
+                        $init_zeroed // This will be `()` if set.
+                    })?
+                    // Create the `this` so it can be referenced by the user inside of the
+                    // expressions creating the individual fields.
+                    $(let $this = unsafe { ::core::ptr::NonNull::new_unchecked(slot) };)?
+                    // Initialize every field.
+                    $crate::__init_internal!(init_slot($($use_data)?):
+                        @data(data),
+                        @slot(slot),
+                        @guards(),
+                        @munch_fields($($fields)*,),
+                    );
+                    // We use unreachable code to ensure that all fields have been mentioned exactly
+                    // once, this struct initializer will still be type-checked and complain with a
+                    // very natural error message if a field is forgotten/mentioned more than once.
+                    #[allow(unreachable_code, clippy::diverging_sub_expression)]
+                    let _ = || {
+                        $crate::__init_internal!(make_initializer:
+                            @slot(slot),
+                            @type_name($t),
+                            @munch_fields($($fields)*,),
+                            @acc(),
+                        );
+                    };
+                }
+                Ok(__InitOk)
+            }
+        );
+        let init = move |slot| -> ::core::result::Result<(), $err> {
+            init(slot).map(|__InitOk| ())
+        };
+        let init = unsafe { $crate::init::$construct_closure::<_, $err>(init) };
+        init
+    }};
+    // `Foo { field <- init, ..base }`: a separate arm (rather than folding `@base` into the
+    // `@zeroed` arm above with more `$(..)?` conditionals) since the two tails need genuinely
+    // different per-field handling (see `init_slot_base` below), not just an optional extra
+    // statement.
+    (with_update_parsed:
+        @this($($this:ident)?),
+        @typ($t:path),
+        @fields($($fields:tt)*),
+        @error($err:ty),
+        // Either `PinData` or `InitData`, `$use_data` should only be present in the `PinData`
+        // case.
+        @data($data:ident, $($use_data:ident)?),
+        // `HasPinData` or `HasInitData`.
+        @has_data($has_data:ident, $get_data:ident),
+        // `pin_init_from_closure` or `init_from_closure`.
+        @construct_closure($construct_closure:ident),
+        @zeroed(),
+        @base($base:expr),
+    ) => {{
+        struct __InitOk;
+        let data = unsafe {
+            use $crate::init::__internal::$has_data;
+            ::kernel::macros::paste!($t::$get_data())
+        };
+        let init = $crate::init::__internal::$data::make_closure::<_, __InitOk, $err>(
+            data,
+            move |slot| {
+                {
+                    struct __InitOk;
+                    // Move `base` into `slot` wholesale, so every field starts out valid (taken
+                    // from `base`); the per-field loop below then overwrites the ones that were
+                    // explicitly mentioned, dropping `base`'s value for that field first.
+                    //
+                    // SAFETY: `slot` is valid for writes, because we are inside of an initializer
+                    // closure.
+                    unsafe { ::core::ptr::write(slot, $base) };
+                    $(let $this = unsafe { ::core::ptr::NonNull::new_unchecked(slot) };)?
+                    $crate::__init_internal!(init_slot_base($($use_data)?):
+                        @data(data),
+                        @slot(slot),
+                        @guards(),
+                        @munch_fields($($fields)*,),
+                    );
+                    #[allow(unreachable_code, clippy::diverging_sub_expression)]
+                    let _ = || {
+                        $crate::__init_internal!(make_initializer:
+                            @slot(slot),
+                            @type_name($t),
+                            @munch_fields($($fields)*,),
+                            @acc(),
+                        );
+                    };
+                }
+                Ok(__InitOk)
+            }
+        );
+        let init = move |slot| -> ::core::result::Result<(), $err> {
+            init(slot).map(|__InitOk| ())
+        };
+        let init = unsafe { $crate::init::$construct_closure::<_, $err>(init) };
+        init
+    }};
+    // --- `#[drop_order(..)]` support ---------------------------------------------------------
+    //
+    // `ordered` is invoked instead of the base case when the initializer is annotated with
+    // `#[drop_order(a, b, ..)]`. It does not change how the fields are initialized at all: it
+    // only emits a compile-time check (`check_drop_order`) that `$order` restates the fields in
+    // `@fields` verbatim, in the same order, before forwarding to the base case unchanged.
+    (ordered:
+        @this($($this:ident)?),
+        @typ($t:path),
+        @fields($($fields:tt)*),
+        @drop_order($($order:ident),* $(,)?),
+        @error($err:ty),
+        @data($data:ident, $($use_data:ident)?),
+        @has_data($has_data:ident, $get_data:ident),
+        @construct_closure($construct_closure:ident),
+    ) => {
+        $crate::__init_internal!(check_drop_order:
+            @order($($order),*),
+            @fields($($fields)*),
+        );
+        $crate::__init_internal!(
+            @this($($this)?),
+            @typ($t),
+            @fields($($fields)*),
+            @error($err),
+            @data($data, $($use_data)?),
+            @has_data($has_data, $get_data),
+            @construct_closure($construct_closure),
+            @munch_fields($($fields)*),
+        )
+    };
+    // --- Enum variant support ---------------------------------------------------------------
+    //
+    // The arm below is the enum-variant counterpart of `with_update_parsed` above. It is invoked
+    // with an extra `@variant($variant:ident)` parameter identifying which variant of `$t` is
+    // being constructed (as generated by `#[pin_data]` on an enum, see `rust/macros/pin_data.rs`,
+    // which emits one projection accessor per `(variant, field)`, named
+    // `$variant__$field`).
+    //
+    // Structs can write each field directly into `(*slot).field`, because `slot` is already
+    // known to hold a value of the right type. Enums cannot: until the discriminant itself has
+    // been written, there is no well-defined field to take the address of (for a non-`repr(C)`
+    // enum, the layout of an un-discriminated value is not even defined). We therefore stage
+    // every field of the chosen variant into its own `MaybeUninit` on the stack (guarded by the
+    // same kind of `DropGuard` as the struct case) and only write into `slot` once, in a single
+    // move, after every field has initialized successfully. This keeps `slot` fully untouched
+    // until the point where it becomes instantly and atomically valid.
+    //
+    // Known limitations (not needed by any current caller): `@this` and `@zeroed` (i.e.
+    // `..Zeroable::zeroed()`) are not supported for enum variants.
+    //
+    // Both the `PinData` (pinned, `$use_data` present) and data-less `InitData` (`init!`, no
+    // `$use_data`) cases are handled below: they only differ in how each field is written into its
+    // `MaybeUninit` storage (`$data.$variant__$field(..)` vs. `Init::__init` directly), exactly
+    // mirroring the struct `init_slot`/`init_slot()` split above.
+    (
+        @this(),
+        @typ($t:path),
+        @variant($variant:ident),
+        @fields($($fields:tt)*),
+        @error($err:ty),
+        @data($data:ident, $use_data:ident),
+        @has_data($has_data:ident, $get_data:ident),
+        @construct_closure($construct_closure:ident),
+    ) => {{
+        struct __InitOk;
+        let data = unsafe {
+            use $crate::init::__internal::$has_data;
+            ::kernel::macros::paste!($t::$get_data())
+        };
+        let init = $crate::init::__internal::$data::make_closure::<_, __InitOk, $err>(
+            data,
+            move |slot| {
+                {
+                    struct __InitOk;
+                    $crate::__init_internal!(init_slot_variant($use_data):
+                        @data(data),
+                        @slot(slot),
+                        @variant($t, $variant),
+                        @guards(),
+                        @acc(),
+                        @munch_fields($($fields)*,),
+                    );
+                    // Same exhaustiveness trick as the struct case, except the dummy struct
+                    // literal is for the chosen variant.
+                    #[allow(unreachable_code, clippy::diverging_sub_expression)]
+                    let _ = || {
+                        $crate::__init_internal!(make_initializer:
+                            @slot(slot),
+                            @type_name($t::$variant),
+                            @munch_fields($($fields)*,),
+                            @acc(),
+                        );
+                    };
+                }
+                Ok(__InitOk)
+            }
+        );
+        let init = move |slot| -> ::core::result::Result<(), $err> {
+            init(slot).map(|__InitOk| ())
+        };
+        let init = unsafe { $crate::init::$construct_closure::<_, $err>(init) };
+        init
+    }};
+    // Data-less (non-pin-data) counterpart of the arm above, for `init!(MyEnum::V { .. })`.
+    (
+        @this(),
+        @typ($t:path),
+        @variant($variant:ident),
+        @fields($($fields:tt)*),
+        @error($err:ty),
+        @data($data:ident,),
+        @has_data($has_data:ident, $get_data:ident),
+        @construct_closure($construct_closure:ident),
+    ) => {{
+        struct __InitOk;
+        let data = unsafe {
+            use $crate::init::__internal::$has_data;
+            ::kernel::macros::paste!($t::$get_data())
+        };
+        let init = $crate::init::__internal::$data::make_closure::<_, __InitOk, $err>(
+            data,
+            move |slot| {
+                {
+                    struct __InitOk;
+                    $crate::__init_internal!(init_slot_variant():
+                        @data(data),
+                        @slot(slot),
+                        @variant($t, $variant),
+                        @guards(),
+                        @acc(),
+                        @munch_fields($($fields)*,),
+                    );
+                    // Same exhaustiveness trick as the struct case, except the dummy struct
+                    // literal is for the chosen variant.
+                    #[allow(unreachable_code, clippy::diverging_sub_expression)]
+                    let _ = || {
+                        $crate::__init_internal!(make_initializer:
+                            @slot(slot),
+                            @type_name($t::$variant),
+                            @munch_fields($($fields)*,),
+                            @acc(),
+                        );
+                    };
+                }
+                Ok(__InitOk)
+            }
+        );
+        let init = move |slot| -> ::core::result::Result<(), $err> {
+            init(slot).map(|__InitOk| ())
+        };
+        let init = unsafe { $crate::init::$construct_closure::<_, $err>(init) };
+        init
+    }};
+    (init_slot_variant($use_data:ident):
+        @data($data:ident),
+        @slot($slot:ident),
+        @variant($t:path, $variant:ident),
+        @guards($($guards:ident,)*),
+        @acc($($acc:tt)*),
+        // In-place initialization syntax.
+        @munch_fields($field:ident <- $val:expr, $($rest:tt)*),
+    ) => {
+        let init = $val;
+        ::kernel::macros::paste! {
+            let mut [<__storage_ $field>] = ::core::mem::MaybeUninit::uninit();
+            // SAFETY: the storage above is local and valid for writes; it is only read back via
+            // `assume_init` once every field of the variant has initialized successfully.
+            unsafe { $data.[<$variant __ $field>]([<__storage_ $field>].as_mut_ptr(), init)? };
+            // SAFETY: we forget the guard once every field has been initialized successfully.
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new([<__storage_ $field>].as_mut_ptr())
+            };
+
+            $crate::__init_internal!(init_slot_variant($use_data):
+                @data($data),
+                @slot($slot),
+                @variant($t, $variant),
+                @guards([<$field>], $($guards,)*),
+                @acc($($acc)* $field: unsafe { [<__storage_ $field>].assume_init() },),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
+    // Data-less (non-pin-data) counterpart of the `<-` arm above, for `init!(MyEnum::V { .. })`:
+    // uses `Init::__init` directly on the field's `MaybeUninit` storage instead of going through
+    // `$data`, exactly like the struct `init_slot()` arms do.
+    (init_slot_variant():
+        @data($data:ident),
+        @slot($slot:ident),
+        @variant($t:path, $variant:ident),
+        @guards($($guards:ident,)*),
+        @acc($($acc:tt)*),
+        @munch_fields($field:ident <- $val:expr, $($rest:tt)*),
+    ) => {
+        let init = $val;
+        ::kernel::macros::paste! {
+            let mut [<__storage_ $field>] = ::core::mem::MaybeUninit::uninit();
+            // SAFETY: the storage above is local and valid for writes; it is only read back via
+            // `assume_init` once every field of the variant has initialized successfully.
+            unsafe { $crate::init::Init::__init(init, [<__storage_ $field>].as_mut_ptr())? };
+            // SAFETY: we forget the guard once every field has been initialized successfully.
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new([<__storage_ $field>].as_mut_ptr())
+            };
+
+            $crate::__init_internal!(init_slot_variant():
+                @data($data),
+                @slot($slot),
+                @variant($t, $variant),
+                @guards([<$field>], $($guards,)*),
+                @acc($($acc)* $field: unsafe { [<__storage_ $field>].assume_init() },),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
+    // Init by-value: does not touch `$data` at all, so (unlike the two arms above) this is shared
+    // between the `PinData` and data-less cases.
+    (init_slot_variant($($use_data:ident)?):
+        @data($data:ident),
+        @slot($slot:ident),
+        @variant($t:path, $variant:ident),
+        @guards($($guards:ident,)*),
+        @acc($($acc:tt)*),
+        @munch_fields($field:ident $(: $val:expr)?, $($rest:tt)*),
+    ) => {
+        ::kernel::macros::paste! {
+            let mut [<__storage_ $field>] = ::core::mem::MaybeUninit::uninit();
+            {
+                $(let $field = $val;)?
+                // SAFETY: the storage above is local and valid for writes.
+                unsafe { ::core::ptr::write([<__storage_ $field>].as_mut_ptr(), $field) };
+            }
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new([<__storage_ $field>].as_mut_ptr())
+            };
+
+            $crate::__init_internal!(init_slot_variant($($use_data)?):
+                @data($data),
+                @slot($slot),
+                @variant($t, $variant),
+                @guards([<$field>], $($guards,)*),
+                @acc($($acc)* $field: unsafe { [<__storage_ $field>].assume_init() },),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
+    (init_slot_variant($($use_data:ident)?):
+        @data($data:ident),
+        @slot($slot:ident),
+        @variant($t:path, $variant:ident),
+        @guards($($guards:ident,)*),
+        @acc($($acc:tt)*),
+        @munch_fields($(,)?),
+    ) => {
+        // Endpoint of munching: every field of the variant has been initialized into its own
+        // local storage above, so we can now dismiss the guards (by forgetting them) and move
+        // everything into `slot` in one go.
+        //
+        // Note this is the one place in the enum-variant path that cannot avoid an intermediate
+        // move: unlike a struct, an enum has no place-expression syntax for projecting into a
+        // variant's fields before the discriminant is written (there is no
+        // `addr_of_mut!((*slot).Variant.field)` -- only `match`, which requires a valid
+        // discriminant to already be in place), so each field is necessarily built in its own
+        // local storage above and moved into `slot` together with the discriminant here.
+        $(::core::mem::forget($guards);)*
+        // SAFETY: every field of the variant has been fully initialized above; `slot` has not
+        // been written to at all until this point, so we write the discriminant and all fields
+        // in a single, atomic move.
+        unsafe {
+            ::kernel::macros::paste!(
+                ::core::ptr::write($slot, $t::$variant { $($acc)* });
+            );
+        }
+    };
+    // --- Tuple-struct and unit-struct support -----------------------------------------------
+    //
+    // Entered as `$crate::__init_internal!(tuple: @this(...), @typ($t), @fields(...), ...)`, for
+    // `pin_init!(Wrapper(inner <- ..., 0: 0))`-style positional initialization. Unlike the enum
+    // case above, an ordinary (non-enum) tuple struct has a well-defined layout for every field
+    // regardless of initialization order, so fields can be written directly into `(*slot).$idx`
+    // exactly like the named-field case -- the only differences are that fields are selected by
+    // a `$idx:literal` tuple index instead of an identifier (dispatched to the `__$idx` accessor
+    // that `#[pin_data]` generates for tuple fields, see `field_accessor_name` in
+    // `rust/macros/pin_data.rs`), and the exhaustiveness typecheck constructs `$t(..)`
+    // positionally instead of `$t { .. }`.
+    //
+    // Known limitation: since the index is supplied per-field rather than derived from position,
+    // there is no check here that every index in `0..field_count` was mentioned exactly once in
+    // ascending order; an out-of-order or incomplete `@fields` list will either fail to compile
+    // (wrong argument count to `$t(..)`) or silently construct the wrong value. The caller (the
+    // not-yet-existing front-end `pin_init!` parser) is expected to always emit indices in
+    // ascending, gapless order, mirroring how it already must emit every named field exactly
+    // once for ordinary structs.
+    (tuple:
+        @this($($this:ident)?),
+        @typ($t:path),
+        @fields($($fields:tt)*),
+        @error($err:ty),
+        @data($data:ident, $($use_data:ident)?),
+        @has_data($has_data:ident, $get_data:ident),
+        @construct_closure($construct_closure:ident),
     ) => {{
-        // We do not want to allow arbitrary returns, so we declare this type as the `Ok` return
-        // type and shadow it later when we insert the arbitrary user code. That way there will be
-        // no possibility of returning without `unsafe`.
         struct __InitOk;
-        // Get the data about fields from the supplied type.
         let data = unsafe {
             use $crate::init::__internal::$has_data;
-            // Here we abuse `paste!` to retokenize `$t`. Declarative macros have some internal
-            // information that is associated to already parsed fragments, so a path fragment
-            // cannot be used in this position. Doing the retokenization results in valid rust
-            // code.
             ::kernel::macros::paste!($t::$get_data())
         };
-        // Ensure that `data` really is of type `$data` and help with type inference:
         let init = $crate::init::__internal::$data::make_closure::<_, __InitOk, $err>(
             data,
             move |slot| {
                 {
-                    // Shadow the structure so it cannot be used to return early.
                     struct __InitOk;
-                    // If `$init_zeroed` is present we should zero the slot now and not emit an
-                    // error when fields are missing (since they will be zeroed). We also have to
-                    // check that the type actually implements `Zeroable`.
-                    $({
-                        fn assert_zeroable<T: $crate::init::Zeroable>(_: *mut T) {}
-                        // Ensure that the struct is indeed `Zeroable`.
-                        assert_zeroable(slot);
-                        // SAFETY: The type implements `Zeroable` by the check above.
-                        unsafe { ::core::ptr::write_bytes(slot, 0, 1) };
-                        $init_zeroed // This will be `()` if set.
-                    })?
-                    // Create the `this` so it can be referenced by the user inside of the
-                    // expressions creating the individual fields.
                     $(let $this = unsafe { ::core::ptr::NonNull::new_unchecked(slot) };)?
-                    // Initialize every field.
-                    $crate::__init_internal!(init_slot($($use_data)?):
+                    $crate::__init_internal!(init_slot_tuple($($use_data)?):
                         @data(data),
                         @slot(slot),
                         @guards(),
                         @munch_fields($($fields)*,),
                     );
-                    // We use unreachable code to ensure that all fields have been mentioned exactly
-                    // once, this struct initializer will still be type-checked and complain with a
-                    // very natural error message if a field is forgotten/mentioned more than once.
                     #[allow(unreachable_code, clippy::diverging_sub_expression)]
                     let _ = || {
-                        $crate::__init_internal!(make_initializer:
+                        $crate::__init_internal!(make_initializer_tuple:
                             @slot(slot),
                             @type_name($t),
                             @munch_fields($($fields)*,),
@@ -663,6 +1161,118 @@ macro_rules! __init_internal {
         let init = unsafe { $crate::init::$construct_closure::<_, $err>(init) };
         init
     }};
+    (init_slot_tuple($($use_data:ident)?):
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        @munch_fields($(,)?),
+    ) => {
+        $(::core::mem::forget($guards);)*
+    };
+    (init_slot_tuple($use_data:ident):
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        // In-place initialization syntax, selected by tuple index.
+        @munch_fields($idx:literal <- $val:expr, $($rest:tt)*),
+    ) => {
+        let init = $val;
+        ::kernel::macros::paste! {
+            // SAFETY: `slot` is valid, we are inside of an initializer closure and return on
+            // error. `$data` picks the right trait (`Init` or `PinInit`) for field `$idx`.
+            unsafe { $data.[<__ $idx>](::core::ptr::addr_of_mut!((*$slot).$idx), init)? };
+            let [<__field_ $idx>] = unsafe {
+                $crate::init::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$idx))
+            };
+
+            $crate::__init_internal!(init_slot_tuple($use_data):
+                @data($data),
+                @slot($slot),
+                @guards([<__field_ $idx>], $($guards,)*),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
+    (init_slot_tuple($($use_data:ident)?):
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        // Init by-value, selected by tuple index.
+        @munch_fields($idx:literal : $val:expr, $($rest:tt)*),
+    ) => {
+        ::kernel::macros::paste! {
+            let [<__val_ $idx>] = $val;
+            // SAFETY: the memory at `(*slot).$idx` is uninitialized.
+            unsafe {
+                ::core::ptr::write(::core::ptr::addr_of_mut!((*$slot).$idx), [<__val_ $idx>])
+            };
+            let [<__field_ $idx>] = unsafe {
+                $crate::init::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$idx))
+            };
+
+            $crate::__init_internal!(init_slot_tuple($($use_data)?):
+                @data($data),
+                @slot($slot),
+                @guards([<__field_ $idx>], $($guards,)*),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
+    (make_initializer_tuple:
+        @slot($slot:ident),
+        @type_name($t:path),
+        @munch_fields($(,)?),
+        @acc($($acc:tt)*),
+    ) => {
+        // Endpoint, nothing more to munch, create the positional initializer. Since we are in
+        // the closure that is never called, this will never get executed.
+        unsafe {
+            ::kernel::macros::paste!(
+                ::core::ptr::write($slot, $t($($acc)*));
+            );
+        }
+    };
+    (make_initializer_tuple:
+        @slot($slot:ident),
+        @type_name($t:path),
+        @munch_fields($idx:literal <- $val:expr, $($rest:tt)*),
+        @acc($($acc:tt)*),
+    ) => {
+        $crate::__init_internal!(make_initializer_tuple:
+            @slot($slot),
+            @type_name($t),
+            @munch_fields($($rest)*),
+            @acc($($acc)* ::core::panic!(),),
+        );
+    };
+    (make_initializer_tuple:
+        @slot($slot:ident),
+        @type_name($t:path),
+        @munch_fields($idx:literal : $val:expr, $($rest:tt)*),
+        @acc($($acc:tt)*),
+    ) => {
+        $crate::__init_internal!(make_initializer_tuple:
+            @slot($slot),
+            @type_name($t),
+            @munch_fields($($rest)*),
+            @acc($($acc)* ::core::panic!(),),
+        );
+    };
+    // A unit struct has no fields to initialize: the value is its own initializer, so we skip
+    // the `PinData`/`InitData` machinery entirely.
+    (unit:
+        @typ($t:path),
+        @error($err:ty),
+        @construct_closure($construct_closure:ident),
+    ) => {{
+        let init = move |slot: *mut $t| -> ::core::result::Result<(), $err> {
+            // SAFETY: `slot` is valid for writes; the unit struct has no fields to set up.
+            unsafe { ::core::ptr::write(slot, $t) };
+            Ok(())
+        };
+        let init = unsafe { $crate::init::$construct_closure::<_, $err>(init) };
+        init
+    }};
     (init_slot($($use_data:ident)?):
         @data($data:ident),
         @slot($slot:ident),
@@ -673,6 +1283,36 @@ macro_rules! __init_internal {
         // have been initialized. Therefore we can now dismiss the guards by forgetting them.
         $(::core::mem::forget($guards);)*
     };
+    (init_slot($use_data:ident): // `use_data` is present, so we use the `data` to init fields.
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        // In-place initialization syntax, converting the sub-initializer's own error via `Into`.
+        @munch_fields($field:ident <-? $val:expr, $($rest:tt)*),
+    ) => {
+        let init = $val;
+        // SAFETY: `slot` is valid, because we are inside of an initializer closure, we
+        // return when an error/panic occurs.
+        // We also use the `data` to require the correct trait (`Init` or `PinInit`) for `$field`.
+        unsafe {
+            $data
+                .$field(::core::ptr::addr_of_mut!((*$slot).$field), init)
+                .map_err(::core::convert::Into::into)?
+        };
+        ::kernel::macros::paste! {
+            // SAFETY: We forget the guard later when initialization has succeeded.
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
+            };
+
+            $crate::__init_internal!(init_slot($use_data):
+                @data($data),
+                @slot($slot),
+                @guards([<$field>], $($guards,)*),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
     (init_slot($use_data:ident): // `use_data` is present, so we use the `data` to init fields.
         @data($data:ident),
         @slot($slot:ident),
@@ -705,6 +1345,34 @@ macro_rules! __init_internal {
             );
         }
     };
+    (init_slot(): // No `use_data`, so we use `Init::__init` directly.
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        // In-place initialization syntax, converting the sub-initializer's own error via `Into`.
+        @munch_fields($field:ident <-? $val:expr, $($rest:tt)*),
+    ) => {
+        let init = $val;
+        // SAFETY: `slot` is valid, because we are inside of an initializer closure, we
+        // return when an error/panic occurs.
+        unsafe {
+            $crate::init::Init::__init(init, ::core::ptr::addr_of_mut!((*$slot).$field))
+                .map_err(::core::convert::Into::into)?
+        };
+        ::kernel::macros::paste! {
+            // SAFETY: We forget the guard later when initialization has succeeded.
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
+            };
+
+            $crate::__init_internal!(init_slot():
+                @data($data),
+                @slot($slot),
+                @guards([<$field>], $($guards,)*),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
     (init_slot(): // No `use_data`, so we use `Init::__init` directly.
         @data($data:ident),
         @slot($slot:ident),
@@ -768,6 +1436,97 @@ macro_rules! __init_internal {
             );
         }
     };
+    // --- `..base` support --------------------------------------------------------------------
+    //
+    // Counterpart of `init_slot` for `Foo { field <- init, ..base }`. `slot` already holds
+    // `base`'s bit pattern for every field (written once in the `with_update_parsed` arm above),
+    // so unlike plain `init_slot`, each explicitly mentioned field must first drop the value
+    // `base` left there before writing its own; once that is done, writing the new value and
+    // guarding it for unwind-safety is identical to the non-`..base` case.
+    //
+    // Known limitation: unlike `init_slot`, fields not mentioned here are never individually
+    // guarded (there is no field list to enumerate them from, only the ones the caller mentioned
+    // explicitly). This matches the existing contract for `..Zeroable::zeroed()` and for
+    // `pin_init_from_closure()` in general (see `rust/kernel/init.rs`): on a failed
+    // initialization the caller only deallocates `slot`'s memory, it never drops it as a whole,
+    // so any field we have not taken explicit responsibility for is simply leaked, not
+    // double-dropped. Supported are `<-` fields on the `PinData` path and by-value fields on both
+    // paths; `<-?`, and `<-` on the data-less (non-pin-data) `init!` path, are not.
+    (init_slot_base($($use_data:ident)?):
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        @munch_fields($(..$base:expr)? $(,)?),
+    ) => {
+        // Endpoint of munching, no fields are left. If execution reaches this point, all
+        // explicitly mentioned fields have been initialized; the rest are still `base`'s.
+        $(::core::mem::forget($guards);)*
+    };
+    (init_slot_base($use_data:ident):
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        // In-place initialization syntax.
+        @munch_fields($field:ident <- $val:expr, $($rest:tt)*),
+    ) => {
+        let init = $val;
+        // SAFETY: `slot` was fully initialized from `base` above, so the value currently at
+        // `$field` is valid and must be disposed of before we overwrite it below.
+        unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*$slot).$field)) };
+        // Call the initializer.
+        //
+        // SAFETY: `slot` is valid, because we are inside of an initializer closure, we
+        // return when an error/panic occurs. `$field` was just dropped above, so this does not
+        // overwrite a live value. We also use the `data` to require the correct trait (`Init` or
+        // `PinInit`) for `$field`.
+        unsafe { $data.$field(::core::ptr::addr_of_mut!((*$slot).$field), init)? };
+        // Create the drop guard:
+        //
+        // We rely on macro hygiene to make it impossible for users to access this local variable.
+        // We use `paste!` to create new hygiene for `$field`.
+        ::kernel::macros::paste! {
+            // SAFETY: We forget the guard later when initialization has succeeded.
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
+            };
+
+            $crate::__init_internal!(init_slot_base($use_data):
+                @data($data),
+                @slot($slot),
+                @guards([<$field>], $($guards,)*),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
+    (init_slot_base($($use_data:ident)?):
+        @data($data:ident),
+        @slot($slot:ident),
+        @guards($($guards:ident,)*),
+        // Init by-value.
+        @munch_fields($field:ident $(: $val:expr)?, $($rest:tt)*),
+    ) => {
+        {
+            $(let $field = $val;)?
+            // SAFETY: `slot` was fully initialized from `base` above, so the value currently at
+            // `$field` is valid and must be disposed of before we overwrite it.
+            unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*$slot).$field)) };
+            // SAFETY: `$field` was just dropped above, so this does not overwrite a live value.
+            unsafe { ::core::ptr::write(::core::ptr::addr_of_mut!((*$slot).$field), $field) };
+        }
+        ::kernel::macros::paste! {
+            // SAFETY: We forget the guard later when initialization has succeeded.
+            let [<$field>] = unsafe {
+                $crate::init::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
+            };
+
+            $crate::__init_internal!(init_slot_base($($use_data)?):
+                @data($data),
+                @slot($slot),
+                @guards([<$field>], $($guards,)*),
+                @munch_fields($($rest)*),
+            );
+        }
+    };
     (make_initializer:
         @slot($slot:ident),
         @type_name($t:path),
@@ -799,6 +1558,35 @@ macro_rules! __init_internal {
             );
         }
     };
+    (make_initializer:
+        @slot($slot:ident),
+        @type_name($t:path),
+        @munch_fields(..$base:expr $(,)?),
+        @acc($($acc:tt)*),
+    ) => {
+        // Endpoint, nothing more to munch, create the initializer. Since the user specified
+        // `..base`, the slot will already hold every field not explicitly mentioned (taken from
+        // `base`). We still check that all fields are actually accessible by using the struct
+        // update syntax ourselves; we use a fresh zeroed placeholder of type `$t`, not `$base`
+        // itself, since this closure is never called and `$base` may already have been moved from
+        // by the time it is type-checked.
+        // We are inside of a closure that is never executed and thus we can abuse `slot` to
+        // get the correct type inference here:
+        #[allow(unused_assignments)]
+        unsafe {
+            let mut placeholder = ::core::mem::zeroed();
+            // We have to use type inference here to make placeholder have the correct type. This
+            // does not get executed, so it has no effect.
+            ::core::ptr::write($slot, placeholder);
+            placeholder = ::core::mem::zeroed();
+            ::kernel::macros::paste!(
+                ::core::ptr::write($slot, $t {
+                    $($acc)*
+                    ..placeholder
+                });
+            );
+        }
+    };
     (make_initializer:
         @slot($slot:ident),
         @type_name($t:path),
@@ -846,4 +1634,100 @@ macro_rules! __init_internal {
             @acc($($acc)* $field: ::core::panic!(),),
         );
     };
+    (make_initializer:
+        @slot($slot:ident),
+        @type_name($t:path),
+        @munch_fields($field:ident <-? $val:expr, $($rest:tt)*),
+        @acc($($acc:tt)*),
+    ) => {
+        $crate::__init_internal!(make_initializer:
+            @slot($slot),
+            @type_name($t),
+            @munch_fields($($rest)*),
+            @acc($($acc)* $field: ::core::panic!(),),
+        );
+    };
+    // Entry point for the `#[drop_order(..)]` check: restate `@order` as a trailing-comma list
+    // and hand it off to `check_drop_order_step` alongside the fields to compare it against.
+    (check_drop_order:
+        @order($($order:ident),* $(,)?),
+        @fields($($fields:tt)*),
+    ) => {
+        $crate::__init_internal!(check_drop_order_step:
+            @order($($order,)*),
+            @fields($($fields)*,),
+        );
+    };
+    // Both lists exhausted at the same time: the hint matches, nothing to emit.
+    (check_drop_order_step:
+        @order(),
+        @fields($(,)?),
+    ) => {};
+    (check_drop_order_step:
+        @order($want:ident, $($rest_order:ident,)*),
+        @fields($have:ident <- $val:expr, $($rest_fields:tt)*),
+    ) => {
+        $crate::__init_internal!(assert_same_ident: @want($want), @have($have));
+        $crate::__init_internal!(check_drop_order_step:
+            @order($($rest_order,)*),
+            @fields($($rest_fields)*),
+        );
+    };
+    (check_drop_order_step:
+        @order($want:ident, $($rest_order:ident,)*),
+        @fields($have:ident <-? $val:expr, $($rest_fields:tt)*),
+    ) => {
+        $crate::__init_internal!(assert_same_ident: @want($want), @have($have));
+        $crate::__init_internal!(check_drop_order_step:
+            @order($($rest_order,)*),
+            @fields($($rest_fields)*),
+        );
+    };
+    (check_drop_order_step:
+        @order($want:ident, $($rest_order:ident,)*),
+        @fields($have:ident $(: $val:expr)?, $($rest_fields:tt)*),
+    ) => {
+        $crate::__init_internal!(assert_same_ident: @want($want), @have($have));
+        $crate::__init_internal!(check_drop_order_step:
+            @order($($rest_order,)*),
+            @fields($($rest_fields)*),
+        );
+    };
+    // Either list ran out before the other: the hint mentions too few/too many/misspelled fields.
+    (check_drop_order_step:
+        @order($($order:tt)*),
+        @fields($($fields:tt)*),
+    ) => {
+        ::core::compile_error!(
+            "`#[drop_order(...)]` must list exactly the same fields, in the same order, as the \
+             initializer they annotate"
+        );
+    };
+    // Compares two field names at compile time. `$want` and `$have` are unrelated `ident`
+    // fragments (one from the attribute, one from the initializer), so `macro_rules` cannot
+    // simply match them against each other; instead we stringify both and compare the bytes in a
+    // `const fn`, which `static_assert!` can then check.
+    (assert_same_ident: @want($want:ident), @have($have:ident)) => {
+        const _: () = {
+            const fn __eq_str(a: &str, b: &str) -> bool {
+                let a = a.as_bytes();
+                let b = b.as_bytes();
+                if a.len() != b.len() {
+                    return false;
+                }
+                let mut i = 0;
+                while i < a.len() {
+                    if a[i] != b[i] {
+                        return false;
+                    }
+                    i += 1;
+                }
+                true
+            }
+            $crate::static_assert!(__eq_str(
+                ::core::stringify!($want),
+                ::core::stringify!($have)
+            ));
+        };
+    };
 }
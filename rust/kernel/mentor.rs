@@ -5,6 +5,7 @@
 //! C headers: [`include/linux/mentor.h`](../../../../include/linux/mentor.h)
 
 use crate::{bindings, error::Error, Result};
+use alloc::vec::Vec;
 
 const TOTAL_WRITES_ADDR: u8 = bindings::MENTOR_TOTAL_WRITES_ADDR as u8;
 
@@ -120,6 +121,109 @@ pub fn read_total_writes() -> u32 {
     unsafe { bindings::mentor_read(TOTAL_WRITES_ADDR) }
 }
 
+/// Writes several `(address, value)` pairs as a single transaction.
+///
+/// Every address is validated before any write is performed, so if any address is invalid, this
+/// returns an error without having written anything at all (i.e. the device is never left
+/// half-programmed).
+///
+/// For a builder-style alternative, see [`Transaction`].
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::prelude::*;
+/// # use kernel::mentor;
+/// # fn test() -> Result {
+/// mentor::write_batch(&[(0x01, 1), (0x02, 2)])?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_batch(writes: &[(u8, u32)]) -> Result {
+    for &(addr, _) in writes {
+        if !is_valid(addr) {
+            return Err(Error::EINVAL);
+        }
+    }
+
+    for &(addr, value) in writes {
+        // SAFETY: FFI call, we have verified above that every address is valid.
+        unsafe { bindings::mentor_write(addr, value) };
+    }
+
+    Ok(())
+}
+
+/// Reads several addresses as a single transaction.
+///
+/// Every address is validated before any read is performed, so if any address is invalid, this
+/// returns an error without touching the hardware at all.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::prelude::*;
+/// # use kernel::mentor;
+/// # fn test() -> Result {
+/// let values = mentor::read_batch(&[0x01, 0x02])?;
+/// assert_eq!(values.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_batch(addrs: &[u8]) -> Result<Vec<u32>> {
+    for &addr in addrs {
+        if !is_valid(addr) {
+            return Err(Error::EINVAL);
+        }
+    }
+
+    let mut values = Vec::with_capacity(addrs.len());
+    for &addr in addrs {
+        // SAFETY: FFI call, we have verified above that every address is valid.
+        values.push(unsafe { bindings::mentor_read(addr) });
+    }
+
+    Ok(values)
+}
+
+/// A builder for a batched, transactional set of register writes.
+///
+/// Addresses are only validated (and writes only performed) once the transaction is
+/// [`commit`](Transaction::commit)ted; see [`write_batch`] for the exact semantics.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::prelude::*;
+/// # use kernel::mentor::Transaction;
+/// # fn test() -> Result {
+/// Transaction::new().write(0x01, 1).write(0x02, 2).commit()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Transaction {
+    writes: Vec<(u8, u32)>,
+}
+
+impl Transaction {
+    /// Creates a new, empty transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a write to `addr` as part of this transaction.
+    pub fn write(mut self, addr: u8, value: u32) -> Self {
+        self.writes.push((addr, value));
+        self
+    }
+
+    /// Validates every queued address and, if all are valid, performs the writes in order.
+    pub fn commit(self) -> Result {
+        write_batch(&self.writes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +234,35 @@ mod tests {
         assert!(is_valid(0x04));
         assert!(!is_valid(0x05));
     }
+
+    #[test]
+    fn test_write_batch_all_valid() {
+        assert!(write_batch(&[(0x00, 1), (0x04, 2)]).is_ok());
+    }
+
+    #[test]
+    fn test_write_batch_rejects_invalid_address() {
+        assert_eq!(
+            write_batch(&[(0x00, 1), (0x05, 2)]),
+            Err(Error::EINVAL)
+        );
+    }
+
+    #[test]
+    fn test_read_batch_all_valid() {
+        assert!(read_batch(&[0x00, 0x04]).is_ok());
+    }
+
+    #[test]
+    fn test_read_batch_rejects_invalid_address() {
+        assert_eq!(read_batch(&[0x00, 0x05]), Err(Error::EINVAL));
+    }
+
+    #[test]
+    fn test_transaction_rejects_invalid_address() {
+        assert_eq!(
+            Transaction::new().write(0x00, 1).write(0x05, 2).commit(),
+            Err(Error::EINVAL)
+        );
+    }
 }
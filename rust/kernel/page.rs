@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Raw physical pages.
+//!
+//! C header: [`include/linux/gfp.h`](../../../../include/linux/gfp.h)
+
+use crate::{alloc::Flags, bindings, error::Error, Result};
+use core::ptr::NonNull;
+
+/// The number of bytes in a page.
+pub const PAGE_SIZE: usize = bindings::PAGE_SIZE as usize;
+
+/// A single, order-0 physical page.
+///
+/// The page is allocated on construction via `alloc_pages` and released via `__free_pages` when
+/// the [`Page`] is dropped. The page is never mapped into kernel virtual memory for longer than a
+/// single closure call: [`Page::with_page_mapped`] and [`Page::with_pointer_into_page`] both map
+/// the page with `kmap_local_page`, run the closure, and unmap with `kunmap_local` before
+/// returning, so the mapped pointer can never escape the closure and outlive the mapping.
+pub struct Page {
+    page: NonNull<bindings::page>,
+}
+
+// SAFETY: `Page` only ever hands out its raw `struct page` pointer through APIs that themselves
+// require a kernel-wide lock or do their own synchronization, and freeing it is the only mutation
+// it performs directly, which is guarded by ownership.
+unsafe impl Send for Page {}
+// SAFETY: See the `Send` impl above.
+unsafe impl Sync for Page {}
+
+impl Page {
+    /// Allocates a new order-0 page with the given allocation `flags`.
+    pub fn alloc_page(flags: Flags) -> Result<Self> {
+        // SAFETY: Calling `alloc_pages` with a valid `gfp_t` and order is always sound; the
+        // return value is checked for null below.
+        let page = unsafe { bindings::alloc_pages(flags.as_raw(), 0) };
+        let page = NonNull::new(page).ok_or(Error::ENOMEM)?;
+        Ok(Self { page })
+    }
+
+    /// Runs `f` with a raw pointer to the start of this page's kernel virtual mapping.
+    ///
+    /// The mapping is released as soon as `f` returns, so the pointer handed to `f` must not be
+    /// retained beyond the call.
+    pub fn with_page_mapped<T>(&self, f: impl FnOnce(*mut u8) -> T) -> T {
+        // SAFETY: `self.page` is a valid page owned by this `Page` for as long as `self` is
+        // borrowed, which covers the whole mapped region used by `f`.
+        let ptr = unsafe { bindings::kmap_local_page(self.page.as_ptr()) }.cast::<u8>();
+        let ret = f(ptr);
+        // SAFETY: `ptr` was just obtained from the matching `kmap_local_page` call above, and is
+        // unmapped here before it could be used again.
+        unsafe { bindings::kunmap_local(ptr.cast()) };
+        ret
+    }
+
+    /// Runs `f` with a raw pointer into this page at byte offset `off`, covering `len` bytes.
+    ///
+    /// Fails with [`Error::EINVAL`] if `[off, off + len)` is not within the page.
+    pub fn with_pointer_into_page<T>(
+        &self,
+        off: usize,
+        len: usize,
+        f: impl FnOnce(*mut u8) -> T,
+    ) -> Result<T> {
+        if off > PAGE_SIZE || len > PAGE_SIZE - off {
+            return Err(Error::EINVAL);
+        }
+        // SAFETY: `off <= PAGE_SIZE`, so `ptr.add(off)` stays within (or one past the end of) the
+        // page mapped by `with_page_mapped`.
+        Ok(self.with_page_mapped(|ptr| f(unsafe { ptr.add(off) })))
+    }
+
+    /// Reads `dst.len()` bytes out of the page starting at offset `off`.
+    pub fn read_raw(&self, dst: &mut [u8], off: usize) -> Result {
+        self.with_pointer_into_page(off, dst.len(), |ptr| {
+            // SAFETY: `with_pointer_into_page` guarantees `ptr` is valid for `dst.len()` bytes.
+            unsafe { core::ptr::copy_nonoverlapping(ptr.cast_const(), dst.as_mut_ptr(), dst.len()) };
+        })
+    }
+
+    /// Writes `src` into the page starting at offset `off`.
+    pub fn write_raw(&self, src: &[u8], off: usize) -> Result {
+        self.with_pointer_into_page(off, src.len(), |ptr| {
+            // SAFETY: `with_pointer_into_page` guarantees `ptr` is valid for `src.len()` bytes.
+            unsafe { core::ptr::copy_nonoverlapping(src.as_ptr(), ptr, src.len()) };
+        })
+    }
+
+    /// Fills `len` bytes of the page, starting at offset `off`, with zeroes.
+    pub fn fill_zero_raw(&self, off: usize, len: usize) -> Result {
+        self.with_pointer_into_page(off, len, |ptr| {
+            // SAFETY: `with_pointer_into_page` guarantees `ptr` is valid for `len` bytes.
+            unsafe { ptr.write_bytes(0, len) };
+        })
+    }
+}
+
+impl Drop for Page {
+    fn drop(&mut self) {
+        // SAFETY: `self.page` was allocated by `alloc_pages` in `Page::alloc_page` and has not
+        // been freed before.
+        unsafe { bindings::__free_pages(self.page.as_ptr(), 0) };
+    }
+}
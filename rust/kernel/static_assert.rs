@@ -9,7 +9,11 @@
 ///   - Boolean assertion: `expr`.
 ///   - Set membership assertion: `(expr) is in {a0, ..., aN}`.
 ///   - Interval membership assertion: `(expr) is in [min, max]`.
+///   - Multi-interval membership assertion: `(expr) is in [min0, max0] | [min1, max1] | ...`.
+///   - Exhaustive-cover assertion: `[min0, max0] | [min1, max1] | ... cover [min, max]`.
 ///   - Fits-in-type assertion: `(expr) fits in type`.
+///   - Alignment assertion: `(expr) is aligned to N`.
+///   - Power-of-two assertion: `(expr) is a power of two`.
 ///
 /// The expressions in all the forms are evaluated in [const context].
 ///
@@ -90,6 +94,60 @@
 /// static_assert!((FOO) is in [MIN_FOO, MAX_FOO]);
 /// ```
 ///
+/// # Multi-interval membership assertion: `(expr) is in [min0, max0] | [min1, max1] | ...`
+///
+/// Statically asserts that the given expression (typically a `const` integer) is in at least one
+/// of the given closed intervals. The negated form (`is not in`) is also available.
+///
+/// ## Examples
+///
+/// ```
+/// static_assert!((-2) is not in [-1, 2] | [10, 20]);
+/// static_assert!((15) is     in [-1, 2] | [10, 20]);
+/// static_assert!((21) is not in [-1, 2] | [10, 20]);
+/// ```
+///
+/// # Exhaustive-cover assertion: `[min0, max0] | [min1, max1] | ... cover [min, max]`
+///
+/// Statically asserts that a list of closed intervals, given in ascending order, exactly tiles
+/// `[min, max]`: the first interval starts at `min`, the last ends at `max`, and every interval
+/// starts exactly where the previous one ended plus one (i.e. there are no gaps or overlaps).
+/// This is useful for statically verifying that e.g. errno partitions, ioctl command-number
+/// ranges or register-offset regions fully and disjointly cover an address space.
+///
+/// ## Examples
+///
+/// ```
+/// static_assert!([0, 9] | [10, 10] | [11, 99] cover [0, 99]);
+/// ```
+///
+/// # Power-of-two assertion: `(expr) is a power of two`
+///
+/// Statically asserts that the given expression (typically a `const` integer) is a power of two.
+/// The negated form (`is not a power of two`) is also available.
+///
+/// ## Examples
+///
+/// ```
+/// static_assert!((0) is not a power of two);
+/// static_assert!((1) is     a power of two);
+/// static_assert!((3) is not a power of two);
+/// static_assert!((4) is     a power of two);
+/// ```
+///
+/// # Alignment assertion: `(expr) is aligned to N`
+///
+/// Statically asserts that the given expression (typically a `const` integer, e.g. a buffer size
+/// or DMA address) is aligned to `N`, which must itself be a power of two. The negated form
+/// (`is not aligned to`) is also available.
+///
+/// ## Examples
+///
+/// ```
+/// static_assert!((bindings::RUST_BUFFER_SIZE) is aligned to 8);
+/// static_assert!((42) is not aligned to 8);
+/// ```
+///
 /// # Fits-in-type assertion: `(expr) fits in type`
 ///
 /// Statically asserts that the given expression (typically a `const` integer) fits in the given
@@ -145,6 +203,43 @@ macro_rules! static_assert {
         static_assert!(!(($expression) >= ($min) && ($expression) <= ($max)));
     };
 
+    // Multi-interval membership assertion: `(expr) is in [min0, max0] | [min1, max1] | ...`.
+    (($expression:expr) is in [$min0:expr, $max0:expr] $(| [$min:expr, $max:expr])+) => {
+        static_assert!(
+            (($expression) >= ($min0) && ($expression) <= ($max0))
+            $(|| (($expression) >= ($min) && ($expression) <= ($max)))+
+        );
+    };
+    (($expression:expr) is not in [$min0:expr, $max0:expr] $(| [$min:expr, $max:expr])+) => {
+        static_assert!(!(
+            (($expression) >= ($min0) && ($expression) <= ($max0))
+            $(|| (($expression) >= ($min) && ($expression) <= ($max)))+
+        ));
+    };
+
+    // Exhaustive-cover assertion: `[min0, max0] | [min1, max1] | ... cover [min, max]`.
+    ([$min0:expr, $max0:expr] $(| [$min:expr, $max:expr])* cover [$lo:expr, $hi:expr]) => {
+        $crate::__static_assert_cover!([$min0, $max0] $(| [$min, $max])*, $lo, $hi);
+    };
+
+    // Power-of-two assertion: `(expr) is a power of two`.
+    (($expression:expr) is a power of two) => {
+        static_assert!( ($expression) != 0 && (($expression) & (($expression) - 1)) == 0 );
+    };
+    (($expression:expr) is not a power of two) => {
+        static_assert!(!(($expression) != 0 && (($expression) & (($expression) - 1)) == 0));
+    };
+
+    // Alignment assertion: `(expr) is aligned to N`.
+    (($expression:expr) is aligned to $align:expr) => {
+        static_assert!(($align) is a power of two);
+        static_assert!( (($expression) & (($align) - 1)) == 0 );
+    };
+    (($expression:expr) is not aligned to $align:expr) => {
+        static_assert!(($align) is a power of two);
+        static_assert!(!((($expression) & (($align) - 1)) == 0));
+    };
+
     // Fits-in-type assertion: `(expr) fits in type`.
     (($expression:expr) fits in $t:ty) => {
         static_assert!(($expression) is     in [<$t>::MIN as i128, <$t>::MAX as i128]);
@@ -154,6 +249,35 @@ macro_rules! static_assert {
     };
 }
 
+/// Helper for [`static_assert`]'s exhaustive-cover form. Not to be used directly.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_assert_cover {
+    ([$min:expr, $max:expr], $lo:expr, $hi:expr) => {
+        static_assert!(($min) == ($lo));
+        static_assert!(($max) == ($hi));
+    };
+    ([$min:expr, $max:expr] $(| [$minN:expr, $maxN:expr])+, $lo:expr, $hi:expr) => {
+        static_assert!(($min) == ($lo));
+        $crate::__static_assert_cover_from!($max, $(| [$minN, $maxN])+, $hi);
+    };
+}
+
+/// Helper for [`__static_assert_cover`], walking the remaining intervals pairwise. Not to be used
+/// directly.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_assert_cover_from {
+    ($prev_max:expr, | [$min:expr, $max:expr], $hi:expr) => {
+        static_assert!(($prev_max) + 1 == ($min));
+        static_assert!(($max) == ($hi));
+    };
+    ($prev_max:expr, | [$min:expr, $max:expr] $(| [$minN:expr, $maxN:expr])+, $hi:expr) => {
+        static_assert!(($prev_max) + 1 == ($min));
+        $crate::__static_assert_cover_from!($max, $(| [$minN, $maxN])+, $hi);
+    };
+}
+
 // Tests.
 //
 // These should later on go into a proper test.
@@ -199,3 +323,27 @@ const MAX_ERRNO: u32 = 4095;
 static_assert!((-(MAX_ERRNO as i128))          fits in i16);
 static_assert!((-(MAX_ERRNO as i128)) does not fit  in i8);
 static_assert!((-(MAX_ERRNO as i128)) does not fit  in u16);
+
+static_assert!((0) is not a power of two);
+static_assert!((1) is     a power of two);
+static_assert!((2) is     a power of two);
+static_assert!((3) is not a power of two);
+static_assert!((4) is     a power of two);
+static_assert!((5) is not a power of two);
+
+static_assert!(( 0) is     aligned to 8);
+static_assert!(( 8) is     aligned to 8);
+static_assert!((16) is     aligned to 8);
+static_assert!(( 1) is not aligned to 8);
+static_assert!((42) is not aligned to 8);
+
+static_assert!(( -2) is not in [-1, 2] | [10, 20]);
+static_assert!(( -1) is     in [-1, 2] | [10, 20]);
+static_assert!((  2) is     in [-1, 2] | [10, 20]);
+static_assert!((  3) is not in [-1, 2] | [10, 20]);
+static_assert!(( 10) is     in [-1, 2] | [10, 20]);
+static_assert!(( 20) is     in [-1, 2] | [10, 20]);
+static_assert!(( 21) is not in [-1, 2] | [10, 20]);
+
+static_assert!([0, 9] | [10, 10] | [11, 99] cover [0, 99]);
+static_assert!([0, 99] cover [0, 99]);
@@ -266,6 +266,59 @@ impl<T: AlwaysRefCounted> Drop for ARef<T> {
     }
 }
 
+/// Types for which any bit pattern is valid.
+///
+/// This is used by [`uaccess`](crate::uaccess) to read a value of `Self` out of a user-space
+/// buffer: since the bytes come from user space, they could be anything, so `Self` must not have
+/// any bit pattern it considers invalid (e.g. an enum discriminant or a `bool` other than `0`/`1`).
+///
+/// # Safety
+///
+/// All bit patterns must be valid values of `Self`. Must not have any padding, as that would be
+/// uninitialized memory after a byte-wise copy.
+pub unsafe trait FromBytes {}
+
+macro_rules! impl_frombytes {
+    ($($t:ty),* $(,)?) => {
+        $(
+            // SAFETY: Any bit pattern is a valid value for the listed types.
+            unsafe impl FromBytes for $t {}
+        )*
+    };
+}
+
+impl_frombytes!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// SAFETY: Every element is `FromBytes` and the array has no padding between elements.
+unsafe impl<T: FromBytes, const N: usize> FromBytes for [T; N] {}
+
+/// Types for which all bytes are initialized, even in the presence of padding.
+///
+/// This is used by [`uaccess`](crate::uaccess) to write a value of `Self` into a user-space
+/// buffer: every byte that makes up `Self`, including any padding bytes a `#[repr(C)]` layout
+/// might introduce, must have been initialized, otherwise the copy would leak uninitialized
+/// kernel memory to user space.
+///
+/// # Safety
+///
+/// Values of this type must be fully initialized, such that every byte of the type, including
+/// padding, can be read without exposing uninitialized memory.
+pub unsafe trait AsBytes {}
+
+macro_rules! impl_asbytes {
+    ($($t:ty),* $(,)?) => {
+        $(
+            // SAFETY: Instances of the listed types are fully initialized and have no padding.
+            unsafe impl AsBytes for $t {}
+        )*
+    };
+}
+
+impl_asbytes!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// SAFETY: Every element is `AsBytes` and the array has no padding between elements.
+unsafe impl<T: AsBytes, const N: usize> AsBytes for [T; N] {}
+
 /// A sum type that always holds either a value of type `L` or `R`.
 pub enum Either<L, R> {
     /// Constructs an instance of [`Either`] containing a value of type `L`.
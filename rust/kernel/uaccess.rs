@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Slices to user space memory.
+//!
+//! C header: [`include/linux/uaccess.h`](../../../../include/linux/uaccess.h)
+
+use crate::{
+    bindings,
+    error::Error,
+    types::{AsBytes, FromBytes},
+    Result,
+};
+use alloc::vec::Vec;
+use core::mem::{size_of, MaybeUninit};
+
+/// A pointer into a userspace process's address space, together with a length.
+///
+/// Constructing a [`UserSlice`] does not dereference the pointer in any way: every byte access
+/// is later performed through [`bindings::copy_from_user`]/[`bindings::copy_to_user`], which are
+/// the only functions in this module that are allowed to touch user memory. This keeps the
+/// pointer itself just an integer-like address as far as Rust is concerned, so a `UserSlice` can
+/// be freely constructed, stored and passed around without any `unsafe` at the call site.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn handle_ioctl(ptr: usize, len: usize) -> Result<u32> {
+///     let mut reader = UserSlice::new(ptr, len).reader();
+///     let header: u32 = reader.read()?;
+///     let mut payload = Vec::new();
+///     reader.read_all(&mut payload)?;
+///     Ok(header)
+/// }
+/// ```
+pub struct UserSlice {
+    ptr: usize,
+    length: usize,
+}
+
+impl UserSlice {
+    /// Creates a new [`UserSlice`] from a user-space address and a length in bytes.
+    ///
+    /// This does not check that `ptr`/`length` actually describe a valid range in the calling
+    /// process's address space; that check happens on every access, inside
+    /// [`bindings::copy_from_user`]/[`bindings::copy_to_user`].
+    pub fn new(ptr: usize, length: usize) -> Self {
+        Self { ptr, length }
+    }
+
+    /// Splits this [`UserSlice`] into a reader and a writer, both starting at the current
+    /// pointer and sharing the same remaining length.
+    pub fn reader_writer(&self) -> (UserSliceReader, UserSliceWriter) {
+        (
+            UserSliceReader {
+                ptr: self.ptr,
+                length: self.length,
+            },
+            UserSliceWriter {
+                ptr: self.ptr,
+                length: self.length,
+            },
+        )
+    }
+
+    /// Returns a [`UserSliceReader`] for this slice.
+    pub fn reader(&self) -> UserSliceReader {
+        UserSliceReader {
+            ptr: self.ptr,
+            length: self.length,
+        }
+    }
+
+    /// Returns a [`UserSliceWriter`] for this slice.
+    pub fn writer(&self) -> UserSliceWriter {
+        UserSliceWriter {
+            ptr: self.ptr,
+            length: self.length,
+        }
+    }
+}
+
+/// A reader for a [`UserSlice`].
+///
+/// Every read advances the internal cursor and shrinks the remaining length, so a driver can
+/// parse a fixed-size header and then consume the trailing payload with further calls, without
+/// having to track offsets itself.
+pub struct UserSliceReader {
+    ptr: usize,
+    length: usize,
+}
+
+impl UserSliceReader {
+    /// Returns the number of bytes left to read.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns whether there are no more bytes left to read.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Reads exactly `out.len()` bytes into `out`, advancing the cursor.
+    ///
+    /// Fails with [`Error::EFAULT`] if `out` is longer than the remaining length, or if the copy
+    /// from user space is only partially successful (e.g. because the range is not actually
+    /// mapped in the calling process).
+    pub fn read_slice(&mut self, out: &mut [u8]) -> Result {
+        if out.len() > self.length {
+            return Err(Error::EFAULT);
+        }
+        // SAFETY: `self.ptr` is treated purely as an address; `copy_from_user` itself validates
+        // that it points into the calling process's address space before touching it. `out` is a
+        // valid Rust slice of the length passed in.
+        let res = unsafe {
+            bindings::copy_from_user(
+                out.as_mut_ptr().cast(),
+                self.ptr as *const core::ffi::c_void,
+                out.len() as u64,
+            )
+        };
+        if res != 0 {
+            return Err(Error::EFAULT);
+        }
+        self.ptr += out.len();
+        self.length -= out.len();
+        Ok(())
+    }
+
+    /// Reads a value of type `T` out of the user slice, advancing the cursor.
+    ///
+    /// `T` must be [`FromBytes`], since the bytes come directly from user space and could be
+    /// anything.
+    pub fn read<T: FromBytes>(&mut self) -> Result<T> {
+        let mut out = MaybeUninit::<T>::uninit();
+        // SAFETY: `out` is valid for writes of `size_of::<T>()` bytes.
+        let buf = unsafe {
+            core::slice::from_raw_parts_mut(out.as_mut_ptr().cast::<u8>(), size_of::<T>())
+        };
+        self.read_slice(buf)?;
+        // SAFETY: `buf` (i.e. all of `out`) has just been fully initialized by `read_slice`
+        // above, and `T: FromBytes` guarantees that every bit pattern is a valid `T`.
+        Ok(unsafe { out.assume_init() })
+    }
+
+    /// Reads the entire remaining contents of the user slice, appending them to `out`.
+    pub fn read_all(&mut self, out: &mut Vec<u8>) -> Result {
+        let len = self.length;
+        let start = out.len();
+        out.resize(start + len, 0);
+        self.read_slice(&mut out[start..])
+    }
+}
+
+/// A writer for a [`UserSlice`].
+///
+/// Every write advances the internal cursor and shrinks the remaining length, mirroring
+/// [`UserSliceReader`].
+pub struct UserSliceWriter {
+    ptr: usize,
+    length: usize,
+}
+
+impl UserSliceWriter {
+    /// Returns the number of bytes left to write.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns whether there is no more room left to write.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Writes all of `data` into the user slice, advancing the cursor.
+    ///
+    /// Fails with [`Error::EFAULT`] if `data` is longer than the remaining length, or if the copy
+    /// to user space is only partially successful.
+    pub fn write_slice(&mut self, data: &[u8]) -> Result {
+        if data.len() > self.length {
+            return Err(Error::EFAULT);
+        }
+        // SAFETY: `self.ptr` is treated purely as an address; `copy_to_user` itself validates
+        // that it points into the calling process's address space before touching it. `data` is a
+        // valid Rust slice of the length passed in.
+        let res = unsafe {
+            bindings::copy_to_user(
+                self.ptr as *mut core::ffi::c_void,
+                data.as_ptr().cast(),
+                data.len() as u64,
+            )
+        };
+        if res != 0 {
+            return Err(Error::EFAULT);
+        }
+        self.ptr += data.len();
+        self.length -= data.len();
+        Ok(())
+    }
+
+    /// Writes a value of type `T` into the user slice, advancing the cursor.
+    ///
+    /// `T` must be [`AsBytes`], so that every byte written (including any padding) is known to
+    /// be initialized.
+    pub fn write<T: AsBytes>(&mut self, value: &T) -> Result {
+        // SAFETY: `value` is a valid `&T` and `T: AsBytes` guarantees every one of its
+        // `size_of::<T>()` bytes, including padding, has been initialized.
+        let buf = unsafe {
+            core::slice::from_raw_parts((value as *const T).cast::<u8>(), size_of::<T>())
+        };
+        self.write_slice(buf)
+    }
+}
@@ -1,16 +1,69 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
+    parse::{Parse, ParseStream},
     parse_quote,
     spanned::Spanned,
     visit_mut::{visit_type_path_mut, VisitMut},
-    Field, Ident, Item, ItemStruct, Path, PathArguments, PathSegment, Token, Type, TypePath,
-    WhereClause,
+    Field, Ident, Item, ItemEnum, ItemStruct, Path, PathArguments, PathSegment, Result, Token,
+    Type, TypePath, Variant, WhereClause,
 };
 
-pub(crate) fn pin_data(args: TokenStream, mut struct_: ItemStruct) -> TokenStream {
+/// The arguments accepted by `#[pin_data]`: nothing, `PinnedDrop`, `!Unpin`, or both (in any
+/// order), e.g. `#[pin_data(PinnedDrop, !Unpin)]`.
+#[derive(Default)]
+struct PinDataArgs {
+    pinned_drop: bool,
+    not_unpin: bool,
+}
+
+impl Parse for PinDataArgs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut this = Self::default();
+        while !input.is_empty() {
+            if input.peek(Token![!]) {
+                input.parse::<Token![!]>()?;
+                let ident: Ident = input.parse()?;
+                if ident != "Unpin" {
+                    return Err(syn::Error::new_spanned(ident, "Expected `!Unpin`."));
+                }
+                this.not_unpin = true;
+            } else {
+                let ident: Ident = input.parse()?;
+                if ident == "PinnedDrop" {
+                    this.pinned_drop = true;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        ident,
+                        "Expected `PinnedDrop` or `!Unpin`.",
+                    ));
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(this)
+    }
+}
+
+pub(crate) fn pin_data(args: TokenStream, item: Item) -> TokenStream {
+    let args = match syn::parse2::<PinDataArgs>(args) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error(),
+    };
+    match item {
+        Item::Struct(struct_) => pin_data_struct(args, struct_),
+        Item::Enum(enum_) => pin_data_enum(args, enum_),
+        _ => syn::Error::new_spanned(item, "`#[pin_data]` may only be used on structs and enums")
+            .to_compile_error(),
+    }
+}
+
+fn pin_data_struct(args: PinDataArgs, mut struct_: ItemStruct) -> TokenStream {
     // The generics might contain the `Self` type. Since this macro will define a new type with the
     // same generics and bounds, this poses a problem: `Self` will refer to the new type as opposed
     // to this struct definition. Therefore we have to replace `Self` with the concrete name.
@@ -22,13 +75,36 @@ pub(crate) fn pin_data(args: TokenStream, mut struct_: ItemStruct) -> TokenStrea
     replacer.visit_generics_mut(&mut struct_.generics);
 
     let the_pin_data = generate_the_pin_data(&struct_);
-    let unpin_impl = unpin_impl(&struct_);
-    let drop_impl = drop_impl(&struct_, args);
+    let unpin_impl = unpin_impl(
+        &struct_.ident,
+        &struct_.generics,
+        struct_
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| (field_accessor_name(idx, f), f)),
+        args.not_unpin,
+    );
+    let drop_impl = drop_impl(&struct_.ident, &struct_.generics, args.pinned_drop);
+    // `.project()`/`.project_replace()` key their generated fields by name, so they are only
+    // generated for structs where every field has one; tuple structs can still be used with
+    // `pin_init!`, just without those two accessors.
+    let all_named = struct_.fields.iter().all(|f| f.ident.is_some());
+    let projection = all_named
+        .then(|| generate_projection(&struct_))
+        .unwrap_or_default();
+    let project_replace = all_named
+        .then(|| generate_project_replace(&struct_))
+        .unwrap_or_default();
 
     let mut errors = TokenStream::new();
-    for field in &mut struct_.fields {
+    for (idx, field) in struct_.fields.iter_mut().enumerate() {
         if !is_pinned(field) && is_phantom_pinned(&field.ty) {
-            let field_name = format!("{}", field.ident.as_ref().unwrap());
+            let field_name = field
+                .ident
+                .as_ref()
+                .map(|i| i.to_string())
+                .unwrap_or_else(|| idx.to_string());
             errors.extend(quote! {
                 ::core::compile_error!(concat!(
                     "The field `",
@@ -44,6 +120,67 @@ pub(crate) fn pin_data(args: TokenStream, mut struct_: ItemStruct) -> TokenStrea
 
         #errors
 
+        const _: () = {
+            #the_pin_data
+
+            #unpin_impl
+
+            #drop_impl
+
+            #projection
+
+            #project_replace
+        };
+    }
+}
+
+fn pin_data_enum(args: PinDataArgs, mut enum_: ItemEnum) -> TokenStream {
+    // Same `Self` replacement dance as for structs, see `pin_data_struct`.
+    let mut replacer = {
+        let name = &enum_.ident;
+        let (_, generics, _) = enum_.generics.split_for_impl();
+        SelfReplacer(parse_quote!(#name #generics))
+    };
+    replacer.visit_generics_mut(&mut enum_.generics);
+
+    let the_pin_data = generate_the_pin_data_enum(&enum_);
+    // Two variants are allowed to have same-named fields (e.g. `A { #[pin] x: Foo }` and
+    // `B { #[pin] x: Bar }`), so each field must be qualified by its variant before being embedded
+    // into `__Unpin`, exactly like the `variant_field_fn_name` accessors already are.
+    let all_fields = enum_.variants.iter().flat_map(|v| {
+        v.fields.iter().map(move |f| {
+            let field_ident = f.ident.as_ref().expect("pin_data only supports named fields");
+            (variant_field_fn_name(&v.ident, field_ident), f)
+        })
+    });
+    let unpin_impl = unpin_impl(&enum_.ident, &enum_.generics, all_fields, args.not_unpin);
+    let drop_impl = drop_impl(&enum_.ident, &enum_.generics, args.pinned_drop);
+
+    let mut errors = TokenStream::new();
+    for variant in &mut enum_.variants {
+        for field in &mut variant.fields {
+            if !is_pinned(field) && is_phantom_pinned(&field.ty) {
+                let field_name = format!("{}", field.ident.as_ref().unwrap());
+                let variant_name = format!("{}", variant.ident);
+                errors.extend(quote! {
+                    ::core::compile_error!(concat!(
+                        "The field `",
+                        #field_name,
+                        "` of variant `",
+                        #variant_name,
+                        "` of type `PhantomPinned` only has an effect, if it has the `#[pin]` \
+                         attribute."
+                    ));
+                });
+            }
+            field.attrs.retain(|a| !a.path().is_ident("pin"));
+        }
+    }
+    quote! {
+        #enum_
+
+        #errors
+
         const _: () = {
             #the_pin_data
 
@@ -108,6 +245,24 @@ fn is_phantom_pinned(ty: &Type) -> bool {
     }
 }
 
+/// The name of the projection accessor function generated for `field` of `variant`.
+///
+/// Variant accessors are disambiguated by prefixing the field name with the variant name, since
+/// two variants of the same enum are allowed to have fields with the same identifier.
+fn variant_field_fn_name(variant: &Ident, field: &Ident) -> Ident {
+    format_ident!("{}__{}", variant, field)
+}
+
+/// The accessor function name used on `__ThePinData` for the `idx`th field of a struct: the
+/// field's own identifier if it has one, or `__idx` for a tuple-struct field (which has no
+/// identifier to reuse as a method name).
+fn field_accessor_name(idx: usize, field: &Field) -> Ident {
+    field
+        .ident
+        .clone()
+        .unwrap_or_else(|| format_ident!("__{idx}"))
+}
+
 fn generate_the_pin_data(
     ItemStruct {
         vis,
@@ -124,10 +279,12 @@ fn generate_the_pin_data(
     // structurally pinned, then it must be initialized via `Init`.
     let pinned_field_accessors = fields
         .iter()
-        .filter(|f| is_pinned(f))
-        .map(|Field { vis, ident, ty, .. }| {
+        .enumerate()
+        .filter(|(_, f)| is_pinned(f))
+        .map(|(idx, field @ Field { vis, ty, .. })| {
+            let name = field_accessor_name(idx, field);
             quote! {
-                #vis unsafe fn #ident<E>(
+                #vis unsafe fn #name<E>(
                     self,
                     slot: *mut #ty,
                     init: impl ::kernel::init::PinInit<#ty, E>,
@@ -139,10 +296,12 @@ fn generate_the_pin_data(
         .collect::<TokenStream>();
     let not_pinned_field_accessors = fields
         .iter()
-        .filter(|f| !is_pinned(f))
-        .map(|Field { vis, ident, ty, .. }| {
+        .enumerate()
+        .filter(|(_, f)| !is_pinned(f))
+        .map(|(idx, field @ Field { vis, ty, .. })| {
+            let name = field_accessor_name(idx, field);
             quote! {
-                #vis unsafe fn #ident<E>(
+                #vis unsafe fn #name<E>(
                     self,
                     slot: *mut #ty,
                     init: impl ::kernel::init::Init<#ty, E>,
@@ -202,25 +361,134 @@ fn generate_the_pin_data(
     }
 }
 
-fn unpin_impl(
-    ItemStruct {
+/// Same as [`generate_the_pin_data`], but for enums: every variant gets its own set of projection
+/// accessors, keyed by variant and field so that two variants sharing a field name do not
+/// collide.
+fn generate_the_pin_data_enum(
+    ItemEnum {
+        vis,
         ident,
         generics,
-        fields,
+        variants,
         ..
-    }: &ItemStruct,
+    }: &ItemEnum,
 ) -> TokenStream {
-    let generics_with_pinlt = {
-        let span = generics.span();
-        let mut g = generics.clone();
-        g.params.insert(0, parse_quote!('__pin));
-        let whr = g.make_where_clause();
-        whr.where_token = Token![where](span);
-        if !whr.predicates.empty_or_trailing() {
-            whr.predicates.push_punct(Default::default());
+    let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+
+    let mut accessors = TokenStream::new();
+    for Variant {
+        ident: variant_ident,
+        fields,
+        ..
+    } in variants
+    {
+        for Field { vis, ident, ty, .. } in fields {
+            let ident = ident.as_ref().expect("pin_data only supports named fields");
+            let fn_name = variant_field_fn_name(variant_ident, ident);
+            if is_pinned_in(fields, ident) {
+                accessors.extend(quote! {
+                    #vis unsafe fn #fn_name<E>(
+                        self,
+                        slot: *mut #ty,
+                        init: impl ::kernel::init::PinInit<#ty, E>,
+                    ) -> ::core::result::Result<(), E> {
+                        unsafe { ::kernel::init::PinInit::__pinned_init(init, slot) }
+                    }
+                });
+            } else {
+                accessors.extend(quote! {
+                    #vis unsafe fn #fn_name<E>(
+                        self,
+                        slot: *mut #ty,
+                        init: impl ::kernel::init::Init<#ty, E>,
+                    ) -> ::core::result::Result<(), E> {
+                        unsafe { ::kernel::init::Init::__init(init, slot) }
+                    }
+                });
+            }
         }
-        g
-    };
+    }
+
+    quote! {
+        // Same shape as the struct version, except the projection functions are keyed by
+        // variant and field (see `#accessors` below).
+        #vis struct __ThePinData #generics
+        {
+            __phantom: ::core::marker::PhantomData<
+                fn(#ident #ty_generics) -> #ident #ty_generics
+            >,
+        }
+
+        impl #impl_generics ::core::clone::Clone for __ThePinData #ty_generics
+            #whr
+        {
+            fn clone(&self) -> Self { *self }
+        }
+
+        impl #impl_generics ::core::marker::Copy for __ThePinData #ty_generics
+            #whr
+        {}
+
+        #[allow(dead_code, non_snake_case)] // Some functions might never be used and private.
+        impl #impl_generics __ThePinData #ty_generics
+            #whr
+        {
+            #accessors
+        }
+
+        // SAFETY: We have added the correct projection functions above to `__ThePinData` and
+        // we also use the least restrictive generics possible.
+        unsafe impl #impl_generics
+            ::kernel::init::__internal::HasPinData for #ident #ty_generics
+            #whr
+        {
+            type PinData = __ThePinData #ty_generics;
+
+            unsafe fn __pin_data() -> Self::PinData {
+                __ThePinData { __phantom: ::core::marker::PhantomData }
+            }
+        }
+
+        unsafe impl #impl_generics
+            ::kernel::init::__internal::PinData for __ThePinData #ty_generics
+            #whr
+        {
+            type Datee = #ident #ty_generics;
+        }
+    }
+}
+
+fn is_pinned_in<'a>(fields: &'a syn::Fields, ident: &Ident) -> bool {
+    fields
+        .iter()
+        .find(|f| f.ident.as_ref() == Some(ident))
+        .is_some_and(is_pinned)
+}
+
+/// Clones `generics` and inserts a fresh `'__pin` lifetime parameter at the front, along with an
+/// (initially empty) `where` clause. Used by both the `Unpin` analysis and the `.project()`
+/// accessor, which both need to tie borrows to the pinned `self`.
+fn with_pin_lifetime(generics: &syn::Generics) -> syn::Generics {
+    let span = generics.span();
+    let mut g = generics.clone();
+    g.params.insert(0, parse_quote!('__pin));
+    let whr = g.make_where_clause();
+    whr.where_token = Token![where](span);
+    if !whr.predicates.empty_or_trailing() {
+        whr.predicates.push_punct(Default::default());
+    }
+    g
+}
+
+/// Generates the `__Unpin` analysis struct and the conditional `Unpin` impl for `ident`, given the
+/// full set of structurally-pinned fields (the union across all variants, for enums).
+fn unpin_impl<'a>(
+    ident: &Ident,
+    generics: &syn::Generics,
+    fields: impl Iterator<Item = (Ident, &'a Field)>,
+    force_not_unpin: bool,
+) -> TokenStream {
+    let generics_with_pinlt = with_pin_lifetime(generics);
     let (
         impl_generics_with_pinlt,
         ty_generics_with_pinlt,
@@ -234,22 +502,35 @@ fn unpin_impl(
     };
     let (_, ty_generics, _) = generics.split_for_impl();
     let mut pinned_fields = fields
-        .iter()
-        .filter(|f| is_pinned(f))
-        .cloned()
+        .filter(|(_, f)| is_pinned(f))
+        .map(|(name, f)| (name, f.clone()))
         .collect::<Vec<_>>();
-    for field in &mut pinned_fields {
+    for (name, field) in &mut pinned_fields {
         field.attrs.retain(|a| !a.path().is_ident("pin"));
+        // `name` is already unique across every field this `__Unpin` analysis struct will embed
+        // (e.g. variant-qualified for enums, see `variant_field_fn_name`), so always rename to it
+        // rather than just for the tuple-field (no-identifier) case.
+        field.ident = Some(name.clone());
+        field.colon_token = Some(<Token![:]>::default());
     }
+    let pinned_fields = pinned_fields.into_iter().map(|(_, f)| f).collect::<Vec<_>>();
+    // When `!Unpin` was requested, we add an unconditionally `!Unpin` marker to `__Unpin`. This
+    // makes the `__Unpin: Unpin` bound below unsatisfiable no matter which fields are structurally
+    // pinned, so `#ident` never implements `Unpin`, even if every one of its fields does.
+    let force_not_unpin_field = force_not_unpin.then(|| {
+        quote! { __phantom_not_unpin: ::core::marker::PhantomPinned, }
+    });
     quote! {
         // This struct will be used for the unpin analysis. It is needed, because only structurally
-        // pinned fields are relevant whether the struct should implement `Unpin`.
+        // pinned fields are relevant whether the struct should implement `Unpin`. For enums, this
+        // is the union of the structurally pinned fields across every variant.
         #[allow(dead_code)] // The fields below are never used.
         struct __Unpin #generics_with_pinlt {
             __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
             __phantom: ::core::marker::PhantomData<
                 fn(#ident #ty_generics) -> #ident #ty_generics
             >,
+            #force_not_unpin_field
             #(#pinned_fields),*
         }
 
@@ -261,18 +542,8 @@ fn unpin_impl(
     }
 }
 
-fn drop_impl(
-    ItemStruct {
-        ident, generics, ..
-    }: &ItemStruct,
-    args: TokenStream,
-) -> TokenStream {
+fn drop_impl(ident: &Ident, generics: &syn::Generics, has_pinned_drop: bool) -> TokenStream {
     let (impl_generics, ty_generics, whr) = generics.split_for_impl();
-    let has_pinned_drop = match syn::parse2::<Option<Ident>>(args) {
-        Ok(None) => false,
-        Ok(Some(ident)) if ident == "PinnedDrop" => true,
-        _ => panic!("Expected nothing or `PinnedDrop` as arguments to `#[pin_data]`."),
-    };
     // We need to disallow normal `Drop` implementation, the exact behavior depends on whether
     // `PinnedDrop` was specified in `args`.
     if has_pinned_drop {
@@ -318,3 +589,178 @@ fn drop_impl(
         }
     }
 }
+
+/// Generates the `__Projection` struct and the `project` method, giving safe, post-init access to
+/// the pinned fields of `struct_` without having to drop to `unsafe { get_unchecked_mut() }`.
+fn generate_projection(
+    ItemStruct {
+        ident,
+        generics,
+        fields,
+        ..
+    }: &ItemStruct,
+) -> TokenStream {
+    let generics_with_pinlt = with_pin_lifetime(generics);
+    let (_, ty_generics_with_pinlt, _) = generics_with_pinlt.split_for_impl();
+    let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+
+    let proj_fields = fields
+        .iter()
+        .map(|field @ Field { vis, ident, ty, .. }| {
+            if is_pinned(field) {
+                quote! { #vis #ident: ::core::pin::Pin<&'__pin mut #ty> }
+            } else {
+                quote! { #vis #ident: &'__pin mut #ty }
+            }
+        });
+
+    let field_inits = fields.iter().map(|field @ Field { ident, .. }| {
+        if is_pinned(field) {
+            quote! {
+                // SAFETY: `this` comes from a `Pin<&mut Self>`, so this field, which is
+                // structurally pinned (has `#[pin]`), may be wrapped back into a `Pin`.
+                #ident: unsafe { ::core::pin::Pin::new_unchecked(&mut this.#ident) }
+            }
+        } else {
+            quote! {
+                // This field is not structurally pinned, so an ordinary mutable borrow is sound.
+                #ident: &mut this.#ident
+            }
+        }
+    });
+
+    quote! {
+        #[allow(dead_code)] // Some fields might never be read, depending on field visibility.
+        struct __Projection #generics_with_pinlt {
+            __phantom: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
+            #(#proj_fields,)*
+        }
+
+        impl #impl_generics #ident #ty_generics
+            #whr
+        {
+            /// Projects this pinned value to a struct of the same shape, where structurally
+            /// pinned fields are projected to `Pin<&mut FieldTy>` and all other fields to
+            /// `&mut FieldTy`.
+            fn project<'__pin>(
+                self: ::core::pin::Pin<&'__pin mut Self>,
+            ) -> __Projection #ty_generics_with_pinlt {
+                // SAFETY: We immediately re-pin every structurally pinned field below, so the
+                // overall pinning invariant of `Self` is upheld.
+                let this = unsafe { ::core::pin::Pin::get_unchecked_mut(self) };
+                __Projection {
+                    __phantom: ::core::marker::PhantomData,
+                    #(#field_inits,)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates the `__ProjOwn` struct and the `project_replace` method, allowing a pinned value to
+/// be transitioned to a new value of the same type in place: structurally pinned fields are
+/// dropped and replaced, while the remaining fields are moved out and handed back to the caller.
+fn generate_project_replace(
+    ItemStruct {
+        ident,
+        generics,
+        fields,
+        ..
+    }: &ItemStruct,
+) -> TokenStream {
+    let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+
+    let owned_field_decls = fields.iter().filter(|f| !is_pinned(f)).map(
+        |Field { vis, ident, ty, .. }| {
+            quote! { #vis #ident: #ty }
+        },
+    );
+    let owned_field_idents = fields
+        .iter()
+        .filter(|f| !is_pinned(f))
+        .map(|f| f.ident.clone());
+
+    let mut steps = TokenStream::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("pin_data only supports named fields");
+        if is_pinned(field) {
+            steps.extend(quote! {
+                // This field is structurally pinned: the old value is dropped in place (it can
+                // never move), then `other`'s value for this field is written into its slot.
+                //
+                // Note that we do *not* additionally guard this slot the way the initializer
+                // macros guard theirs: `this` is already a live, externally-owned `Self`, so once
+                // this write completes, the slot legitimately holds a new, valid value that will
+                // be dropped exactly once, whenever `this`'s real owner eventually drops it
+                // normally. Arming a guard here would drop that same value a second time.
+                unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*this).#ident)) };
+                unsafe {
+                    ::core::ptr::write(
+                        ::core::ptr::addr_of_mut!((*this).#ident),
+                        ::core::ptr::read(::core::ptr::addr_of!((*other_ptr).#ident)),
+                    )
+                };
+            });
+        } else {
+            steps.extend(quote! {
+                // This field is not structurally pinned: move it out for the caller, then move
+                // `other`'s value for this field into its slot.
+                let #ident = unsafe { ::core::ptr::read(::core::ptr::addr_of!((*this).#ident)) };
+                unsafe {
+                    ::core::ptr::write(
+                        ::core::ptr::addr_of_mut!((*this).#ident),
+                        ::core::ptr::read(::core::ptr::addr_of!((*other_ptr).#ident)),
+                    )
+                };
+            });
+        }
+    }
+
+    quote! {
+        #[allow(dead_code)] // Some fields might never be read, depending on field visibility.
+        struct __ProjOwn #generics
+            #whr
+        {
+            #(#owned_field_decls,)*
+        }
+
+        impl #impl_generics #ident #ty_generics
+            #whr
+        {
+            /// Replaces the pinned fields of `self` with the ones from `other` in place,
+            /// returning the previous values of the non-structurally-pinned fields.
+            fn project_replace(self: ::core::pin::Pin<&mut Self>, other: Self) -> __ProjOwn #ty_generics {
+                // SAFETY: We never move out of `this` (we only read/write/drop in place), so the
+                // overall pinning invariant of `Self` is upheld.
+                let this: *mut Self = unsafe { ::core::pin::Pin::get_unchecked_mut(self) };
+                // Suppress `other`'s destructor: every one of its fields is moved out below.
+                let other = ::core::mem::ManuallyDrop::new(other);
+                let other_ptr: *const Self = &*other;
+
+                // If a pinned field's destructor panics partway through `#steps`, that field's
+                // slot is left in an inconsistent state (neither cleanly the old value nor the
+                // new one), and `this`'s real owner will still run its normal destructor over it
+                // again once this unwind reaches them, which would double-drop that field. There
+                // is no general way to recover from that, so rather than let unwinding continue
+                // into unsoundness, abort. This guard is defused once every field has been
+                // transitioned successfully.
+                struct __AbortOnUnwind;
+                impl ::core::ops::Drop for __AbortOnUnwind {
+                    fn drop(&mut self) {
+                        // SAFETY: `BUG` has no preconditions; it never returns.
+                        unsafe { ::kernel::bindings::BUG() };
+                    }
+                }
+                let __abort_on_unwind = __AbortOnUnwind;
+
+                #steps
+
+                ::core::mem::forget(__abort_on_unwind);
+
+                __ProjOwn {
+                    #(#owned_field_idents,)*
+                }
+            }
+        }
+    }
+}
@@ -41,7 +41,7 @@ pub(crate) fn primitive_init(
         Ok(init_kind) => init_kind,
         Err(err) => return err.to_compile_error(),
     };
-    let zeroable_check = match init_kind {
+    let zeroable_check = match &init_kind {
         InitKind::Normal => quote! {},
 
         InitKind::Zeroing => quote! {
@@ -57,6 +57,17 @@ pub(crate) fn primitive_init(
             // SAFETY: The type implements `Zeroable` by the check above.
             unsafe { ::core::ptr::write_bytes(slot, 0, 1) };
         },
+
+        InitKind::FromBase(base) => quote! {
+            // The user specified `..base` at the end of the list of fields. We materialize
+            // `base` and move the whole value into `slot` up front; the fields mentioned above
+            // are then applied on top, each dropping the value that came from `base` right
+            // before it is overwritten (see `init_fields`).
+            let __init_base = #base;
+            // SAFETY: `slot` is uninitialized and `__init_base` is moved into it below, so we
+            // must not (and do not) use `__init_base` again afterwards.
+            unsafe { ::core::ptr::write(slot, __init_base) };
+        },
     };
     let this = match this {
         None => quote!(),
@@ -66,7 +77,8 @@ pub(crate) fn primitive_init(
             let #ident = unsafe { ::core::ptr::NonNull::new_unchecked(slot) };
         },
     };
-    let init_fields = init_fields(&fields, use_data);
+    let from_base = matches!(init_kind, InitKind::FromBase(_));
+    let init_fields = init_fields(&fields, use_data, from_base);
     let field_check = make_field_check(&fields, init_kind, &path);
     quote! {{
         // We do not want to allow arbitrary returns, so we declare this type as the `Ok` return
@@ -110,59 +122,68 @@ pub(crate) fn primitive_init(
 enum InitKind {
     Normal,
     Zeroing,
+    /// The user specified `..base_expr`, where `base_expr` is some expression other than
+    /// `Zeroable::zeroed()`. All fields not explicitly mentioned are taken from `base`.
+    FromBase(Expr),
 }
 
 fn get_init_kind(rest: Option<(Token![..], Expr)>) -> Result<InitKind> {
-    let Some((dotdot, expr)) = rest else {
+    let Some((_, expr)) = rest else {
         return Ok(InitKind::Normal);
     };
-    let tokens = quote!(#dotdot #expr);
-    macro_rules! bail {
-        () => {{
-            return Err(syn::Error::new_spanned(
-                tokens,
-                "Expected one of the following:\n- Nothing.\n- `..Zeroable::zeroed()`.",
-            ));
-        }};
-    }
-    let Expr::Call(ExprCall {
+    if let Expr::Call(ExprCall {
         func, args, attrs, ..
-    }) = expr
-    else {
-        bail!()
-    };
-    if !args.is_empty() || !attrs.is_empty() {
-        bail!()
-    }
-    match *func {
-        Expr::Path(ExprPath {
-            attrs,
-            qself: None,
-            path:
-                Path {
-                    leading_colon: None,
-                    segments,
-                },
-        }) if attrs.is_empty()
-            && segments.len() == 2
-            && segments[0].ident == "Zeroable"
-            && segments[0].arguments.is_none()
-            && segments[1].ident == "zeroed"
-            && segments[1].arguments.is_none() =>
-        {
-            Ok(InitKind::Zeroing)
+    }) = &expr
+    {
+        if args.is_empty() && attrs.is_empty() {
+            if let Expr::Path(ExprPath {
+                attrs,
+                qself: None,
+                path:
+                    Path {
+                        leading_colon: None,
+                        segments,
+                    },
+            }) = &**func
+            {
+                if attrs.is_empty()
+                    && segments.len() == 2
+                    && segments[0].ident == "Zeroable"
+                    && segments[0].arguments.is_none()
+                    && segments[1].ident == "zeroed"
+                    && segments[1].arguments.is_none()
+                {
+                    return Ok(InitKind::Zeroing);
+                }
+            }
         }
-        _ => bail!(),
     }
+    // Any other expression is taken as a struct-update base: the fields not mentioned
+    // explicitly are taken from `base`.
+    Ok(InitKind::FromBase(expr))
 }
 
-fn init_fields(fields: &Punctuated<FieldInitializer, Token![,]>, use_data: bool) -> TokenStream {
+fn init_fields(
+    fields: &Punctuated<FieldInitializer, Token![,]>,
+    use_data: bool,
+    from_base: bool,
+) -> TokenStream {
     let mut guards = vec![];
     let mut res = TokenStream::new();
     for field in fields {
         let ident = field.ident();
         let guard = format_ident!("__{ident}_guard", span = Span::call_site());
         guards.push(guard.clone());
+        // When initializing from a base, `slot.#ident` already holds the value that `base`
+        // provided for this field; it must be dropped before we overwrite it, since the memory
+        // is not uninitialized like in the other cases.
+        let drop_base_field = from_base.then(|| {
+            quote! {
+                // SAFETY: `slot.#ident` currently holds the value taken from `base` and has not
+                // been dropped yet.
+                unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*slot).#ident)) };
+            }
+        });
         let init = match field {
             FieldInitializer::Value { ident, value } => {
                 let value = value
@@ -172,28 +193,44 @@ fn init_fields(fields: &Punctuated<FieldInitializer, Token![,]>, use_data: bool)
                 quote! {
                     {
                         #value
+                        #drop_base_field
                         // Initialize the field.
                         //
-                        // SAFETY: The memory at `slot` is uninitialized.
+                        // SAFETY: The memory at `slot` is uninitialized (or was just dropped
+                        // in place above, in which case it is uninitialized again).
                         unsafe { ::core::ptr::write(::core::ptr::addr_of_mut!((*slot).#ident), #ident) };
                     }
                 }
             }
-            FieldInitializer::Init { ident, value, .. } => {
+            FieldInitializer::Init {
+                ident,
+                value,
+                convert,
+                ..
+            } => {
+                // With `<-?`, the field initializer is allowed to fail with its own error type;
+                // convert it to the outer `error` via `Into`/`From` at the `?` site.
+                let map_err = convert.is_some().then(|| {
+                    quote! { .map_err(::core::convert::Into::into) }
+                });
                 if use_data {
                     quote! {
                         let init = #value;
+                        #drop_base_field
                         // Call the initializer.
                         //
                         // SAFETY: `slot` is valid, because we are inside of an initializer closure,
                         // we return when an error/panic occurs.
                         // We also use the `data` to require the correct trait (`Init` or `PinInit`)
                         // for `#ident`.
-                        unsafe { data.#ident(::core::ptr::addr_of_mut!((*slot).#ident), init)? };
+                        unsafe {
+                            data.#ident(::core::ptr::addr_of_mut!((*slot).#ident), init)#map_err?
+                        };
                     }
                 } else {
                     quote! {
                         let init = #value;
+                        #drop_base_field
                         // Call the initializer.
                         //
                         // SAFETY: `slot` is valid, because we are inside of an initializer closure,
@@ -202,7 +239,7 @@ fn init_fields(fields: &Punctuated<FieldInitializer, Token![,]>, use_data: bool)
                             ::kernel::init::Init::__init(
                                 init,
                                 ::core::ptr::addr_of_mut!((*slot).#ident),
-                            )?
+                            )#map_err?
                         };
                     }
                 }
@@ -226,6 +263,14 @@ fn init_fields(fields: &Punctuated<FieldInitializer, Token![,]>, use_data: bool)
         #res
         // If execution reaches this point, all fields have been initialized. Therefore we can now
         // dismiss the guards by forgetting them.
+        //
+        // Note for the `..base` case: a guard per overwritten field is all that is needed here.
+        // A field's guard is only created once its new value is in place; if a `<-` initializer
+        // fails after the `base` value of its field was dropped, that field is left
+        // uninitialized, and it is not dropped again. Fields taken from `base` but never
+        // mentioned above are simply part of the valid value at `slot`; if a later field fails to
+        // initialize, `slot` as a whole is considered uninitialized by our caller and is not
+        // dropped, so those fields are leaked rather than used after being freed.
         #(::core::mem::forget(#guards);)*
     }
 }
@@ -270,6 +315,28 @@ fn make_field_check(
                 })
             };
         },
+
+        InitKind::FromBase(_) => quote! {
+            // We use unreachable code to ensure that all fields have been mentioned at most once.
+            // Since the user specified `..base` at the end, all missing fields will be taken from
+            // `base`. This struct initializer will still be type-checked and complain with a very
+            // natural error message if a field is mentioned more than once, or doesn't exist.
+            // A zeroed placeholder of the same type stands in for `base` here, since `base` has
+            // already been moved into `slot` by the time this is type-checked.
+            #[allow(unreachable_code, clippy::diverging_sub_expression, unused_assignments)]
+            // SAFETY: this code is never executed.
+            let _ = || unsafe {
+                let mut base = ::core::mem::zeroed();
+                ::core::ptr::write(slot, base);
+                base = ::core::mem::zeroed();
+                ::core::ptr::write(slot, #path {
+                    #(
+                        #fields: ::core::panic!(),
+                    )*
+                    ..base
+                })
+            };
+        },
     }
 }
 
@@ -352,6 +419,10 @@ enum FieldInitializer {
     Init {
         ident: Ident,
         _larrow: Token![<-],
+        /// Present when the field was written as `name <-? expr`: the field initializer's own
+        /// error type may differ from the outer `error`, and is converted via `Into`/`From` at
+        /// the `?` site instead of being propagated as-is.
+        convert: Option<Token![?]>,
         value: Expr,
     },
 }
@@ -372,6 +443,7 @@ impl Parse for FieldInitializer {
             Self::Init {
                 ident,
                 _larrow: input.parse()?,
+                convert: input.peek(Token![?]).then(|| input.parse()).transpose()?,
                 value: input.parse()?,
             }
         } else if lookahead.peek(Token![:]) {
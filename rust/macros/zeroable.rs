@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Field, Type};
+
+/// Implements a structural `#[derive(Zeroable)]`.
+///
+/// This gathers the types of all constituent fields (deduplicated) and emits
+/// `unsafe impl<...> Zeroable for Self where Field0Ty: Zeroable, Field1Ty: Zeroable, ... {}`, so
+/// that the derived impl is correct even for generic structs: the all-zero bit pattern of `Self`
+/// is only valid if the all-zero bit pattern is valid for every field.
+///
+/// `PhantomData<T>` fields are skipped when gathering field types, since `PhantomData<T>` is
+/// `Zeroable` regardless of `T`. Consequently, a type parameter that only ever appears inside a
+/// `PhantomData` field does not get a `Zeroable` bound.
+pub(crate) fn zeroable(input: DeriveInput) -> TokenStream {
+    let DeriveInput {
+        ident,
+        mut generics,
+        data,
+        ..
+    } = input;
+
+    let fields = match data {
+        Data::Struct(data) => data.fields,
+        Data::Enum(_) => {
+            return syn::Error::new_spanned(
+                ident,
+                "`Zeroable` cannot be derived for enums, since the all-zero bit pattern is not \
+                 guaranteed to be a valid discriminant.",
+            )
+            .to_compile_error();
+        }
+        Data::Union(_) => {
+            return syn::Error::new_spanned(ident, "`Zeroable` cannot be derived for unions.")
+                .to_compile_error();
+        }
+    };
+
+    let mut field_tys: Vec<Type> = vec![];
+    for Field { ty, .. } in &fields {
+        if is_phantom_data(ty) {
+            continue;
+        }
+        if !field_tys.contains(ty) {
+            field_tys.push(ty.clone());
+        }
+    }
+
+    let checks = field_tys.iter().map(|ty| {
+        quote! {
+            const _: () = {
+                fn assert<T: ?::core::marker::Sized + ::kernel::init::Zeroable>() {}
+                // We never call this, we only need it to type-check.
+                #[allow(unreachable_code, clippy::diverging_sub_expression)]
+                let _ = || assert::<#ty>();
+            };
+        }
+    });
+
+    {
+        let where_clause = generics.make_where_clause();
+        for ty in &field_tys {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: ::kernel::init::Zeroable));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #(#checks)*
+
+        // SAFETY: every constituent field type is `Zeroable` (checked above via the bounds on
+        // this impl), so the all-zero bit pattern is a valid value for each of them and thus for
+        // `#ident` as a whole.
+        unsafe impl #impl_generics ::kernel::init::Zeroable for #ident #ty_generics #where_clause {}
+    }
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    matches!(ty, Type::Path(ty) if ty.path.segments.last().is_some_and(|s| s.ident == "PhantomData"))
+}